@@ -0,0 +1,157 @@
+use crate::{palette::LengthMismatch, pixel::rgb::{DeltaEMethod, RgbPixel, ACHROMATIC_CHROMA}};
+
+/// Counts how many pixels fall into each of `bins` even ranges of (OKLCH) lightness, from black to white.
+///
+/// Lightnesses outside of `0.0~1.0` (such as HDR colours) are counted in the first or last bin.
+pub fn lightness_histogram(pixels: &[RgbPixel], bins: usize) -> Vec<usize> {
+    let mut histogram = vec![0; bins];
+    if bins == 0 {
+        return histogram;
+    }
+
+    for pixel in pixels {
+        let lightness = pixel.as_oklch().0;
+        histogram[bin_for(lightness, bins)] += 1;
+    }
+
+    histogram
+}
+
+/// Counts how many pixels fall into each of `bins` even ranges of (OKLCH) hue, starting at `0.0`.
+///
+/// Achromatic pixels (greys) have no hue, and aren't counted - so the bins add up to the number of chromatic pixels.
+pub fn hue_histogram(pixels: &[RgbPixel], bins: usize) -> Vec<usize> {
+    let mut histogram = vec![0; bins];
+    if bins == 0 {
+        return histogram;
+    }
+
+    for pixel in pixels {
+        let (_, chroma, hue) = pixel.as_oklch().get();
+        if hue.is_nan() || chroma < ACHROMATIC_CHROMA {
+            continue;
+        }
+        histogram[bin_for(hue.rem_euclid(360.0) / 360.0, bins)] += 1;
+    }
+
+    histogram
+}
+
+/// Measures the difference between each pair of pixels in two images - a heatmap of where they differ,
+/// for visually diffing rendered output.
+///
+/// The images must have the same number of pixels.
+pub fn delta_e_map(a: &[RgbPixel], b: &[RgbPixel], metric: DeltaEMethod) -> Result<Vec<f32>, LengthMismatch> {
+    if a.len() != b.len() {
+        return Err(LengthMismatch { expected: a.len(), found: b.len() });
+    }
+
+    Ok(a.iter().zip(b).map(|(a, b)| a.delta_e(b, metric)).collect())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+/// A summary of a map of differences (from `delta_e_map`).
+pub struct DeltaEStats {
+    /// The average difference.
+    pub mean: f32,
+    /// The 95th percentile - 95% of the differences are at most this. Less sensitive to a few outliers than `max`.
+    pub p95: f32,
+    /// The largest difference.
+    pub max: f32,
+}
+
+impl DeltaEStats {
+    /// Summarises a map of differences. An empty map has no difference (every stat is `0.0`).
+    pub fn new(map: &[f32]) -> DeltaEStats {
+        if map.is_empty() {
+            return DeltaEStats { mean: 0.0, p95: 0.0, max: 0.0 };
+        }
+
+        let mut sorted = map.to_vec();
+        sorted.sort_by(f32::total_cmp);
+
+        // nearest-rank percentile
+        let p95_rank = ((sorted.len() as f32 * 0.95).ceil() as usize).max(1);
+
+        DeltaEStats {
+            mean: sorted.iter().sum::<f32>() / sorted.len() as f32,
+            p95: sorted[p95_rank - 1],
+            max: sorted[sorted.len() - 1],
+        }
+    }
+}
+
+/// Retrieves the bin for a value between `0.0` and `1.0`, clamping anything outside of it.
+fn bin_for(value: f32, bins: usize) -> usize {
+    ((value * bins as f32).max(0.0) as usize).min(bins - 1)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{palette::LengthMismatch, pixel::{oklch::OklchPixel, rgb::{colours, DeltaEMethod, Gamut, RgbPixel}}};
+
+    use super::{delta_e_map, hue_histogram, lightness_histogram, DeltaEStats};
+
+    #[test]
+    fn lightness_histogram_shape() {
+        // 1 black, 2 mid-greys, and 3 whites
+        let grey = OklchPixel(0.55, 0.0, 0.0).gamut_map(Gamut::Srgb);
+        let image = [colours::BLACK, grey, grey, colours::WHITE, colours::WHITE, colours::WHITE];
+
+        assert_eq!(lightness_histogram(&image, 4), vec![1, 0, 2, 3]);
+        assert!(lightness_histogram(&image, 0).is_empty());
+    }
+
+    #[test]
+    fn hue_histogram_skips_greys() {
+        let image = [
+            colours::RED,
+            colours::RED,
+            colours::BLUE,
+            colours::BLACK,
+            RgbPixel(0.5, 0.5, 0.5),
+            colours::WHITE,
+        ];
+        let histogram = hue_histogram(&image, 12);
+
+        assert_eq!(histogram.iter().sum::<usize>(), 3);
+        assert_eq!(histogram[colours::RED.as_oklch().2 as usize / 30], 2);
+        assert_eq!(histogram[colours::BLUE.as_oklch().2 as usize / 30], 1);
+    }
+
+    #[test]
+    fn identical_images_have_no_difference() {
+        let image = vec![colours::RED, colours::GREEN, colours::BLUE, colours::WHITE];
+        let map = delta_e_map(&image, &image, DeltaEMethod::Ciede2000).unwrap();
+
+        assert!(map.iter().all(|difference| *difference == 0.0));
+        assert_eq!(DeltaEStats::new(&map), DeltaEStats { mean: 0.0, p95: 0.0, max: 0.0 });
+    }
+
+    #[test]
+    fn changed_pixel_shows_up_at_its_index() {
+        let original = vec![colours::RED; 20];
+        let mut changed = original.clone();
+        changed[7] = colours::BLUE;
+
+        let map = delta_e_map(&original, &changed, DeltaEMethod::Ok).unwrap();
+        let worst = map.iter().enumerate().fold(0, |worst, (index, difference)| if *difference > map[worst] { index } else { worst });
+        assert_eq!(worst, 7);
+
+        let stats = DeltaEStats::new(&map);
+        assert_eq!(stats.max, map[7]);
+        assert_eq!(stats.p95, 0.0);
+        assert!((stats.mean - map[7] / 20.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn delta_e_map_validates_lengths() {
+        assert_eq!(
+            delta_e_map(&[colours::RED], &[], DeltaEMethod::Cie76).unwrap_err(),
+            LengthMismatch { expected: 1, found: 0 },
+        );
+
+        let error = delta_e_map(&[colours::RED, colours::RED], &[colours::RED], DeltaEMethod::Ok).unwrap_err();
+        assert_eq!(error.to_string(), "expected 2 values (one per pixel), found 1");
+    }
+}
@@ -0,0 +1,306 @@
+use std::ops::RangeInclusive;
+
+use crate::pixel::{hsl::HslPixel, lab::LabPixel, lch::LchPixel, oklab::OklabPixel, oklch::OklchPixel, rgb::RgbPixel};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(rename_all = "lowercase"))]
+/// The colour spaces that a `Colour` can be in.
+pub enum ColourSpace {
+    Rgb,
+    Hsl,
+    Lab,
+    Lch,
+    Oklab,
+    Oklch,
+}
+
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(try_from = "TaggedColour", into = "TaggedColour"))]
+/// A colour that remembers which colour space it's in.
+///
+/// Each pixel type is statically typed, which is great until you need to pass colours around
+/// without knowing their space ahead of time. `Colour` wraps each of them, so you can convert
+/// between spaces at runtime:
+///
+/// ```ignore
+/// let colour = Colour::Lch(LchPixel(50.0, 40.0, 120.0));
+/// let rgb = colour.to(ColourSpace::Rgb);
+/// ```
+///
+/// With the `serde` feature, it's (de)serialized along with its space - as `{"space": "oklch", "values": [0.7, 0.15, 30.0]}`.
+/// Deserializing checks that each value is within the range of its space. Achromatic (`NaN`) hues are written
+/// as `null`, since JSON can't represent `NaN` - any other `null` is rejected.
+pub enum Colour {
+    Rgb(RgbPixel),
+    Hsl(HslPixel),
+    Lab(LabPixel),
+    Lch(LchPixel),
+    Oklab(OklabPixel),
+    Oklch(OklchPixel),
+}
+
+impl From<RgbPixel> for Colour {
+    fn from(value: RgbPixel) -> Self {
+        Colour::Rgb(value)
+    }
+}
+
+impl From<HslPixel> for Colour {
+    fn from(value: HslPixel) -> Self {
+        Colour::Hsl(value)
+    }
+}
+
+impl From<LabPixel> for Colour {
+    fn from(value: LabPixel) -> Self {
+        Colour::Lab(value)
+    }
+}
+
+impl From<LchPixel> for Colour {
+    fn from(value: LchPixel) -> Self {
+        Colour::Lch(value)
+    }
+}
+
+impl From<OklabPixel> for Colour {
+    fn from(value: OklabPixel) -> Self {
+        Colour::Oklab(value)
+    }
+}
+
+impl From<OklchPixel> for Colour {
+    fn from(value: OklchPixel) -> Self {
+        Colour::Oklch(value)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+/// An error for when a component of a colour is outside the range of its space.
+pub struct ComponentOutOfRange {
+    pub space: ColourSpace,
+    /// Which of the 3 components was out of range.
+    pub index: usize,
+    pub value: f32,
+}
+
+impl std::fmt::Display for ComponentOutOfRange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "component {} of a {:?} colour is out of range: {}", self.index, self.space, self.value)
+    }
+}
+
+impl std::error::Error for ComponentOutOfRange {}
+
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// The serialized form of a `Colour`. `NaN` components are stored as `None`.
+struct TaggedColour {
+    space: ColourSpace,
+    values: [Option<f32>; 3],
+}
+
+impl From<Colour> for TaggedColour {
+    fn from(value: Colour) -> Self {
+        let (a, b, c) = value.get();
+        let value_or_none = |component: f32| (!component.is_nan()).then_some(component);
+        TaggedColour { space: value.space(), values: [value_or_none(a), value_or_none(b), value_or_none(c)] }
+    }
+}
+
+impl TryFrom<TaggedColour> for Colour {
+    type Error = ComponentOutOfRange;
+
+    fn try_from(value: TaggedColour) -> Result<Self, Self::Error> {
+        let TaggedColour { space, values } = value;
+        // (the index of the hue, if there is one)
+        let (ranges, hue): ([RangeInclusive<f32>; 3], Option<usize>) = match space {
+            ColourSpace::Rgb => ([RgbPixel::R_RANGE, RgbPixel::G_RANGE, RgbPixel::B_RANGE], None),
+            ColourSpace::Hsl => ([HslPixel::H_RANGE, HslPixel::S_RANGE, HslPixel::L_RANGE], Some(0)),
+            ColourSpace::Lab => ([LabPixel::L_RANGE, LabPixel::A_RANGE, LabPixel::B_RANGE], None),
+            ColourSpace::Lch => ([LchPixel::L_RANGE, LchPixel::C_RANGE, LchPixel::H_RANGE], Some(2)),
+            ColourSpace::Oklab => ([OklabPixel::L_RANGE, OklabPixel::A_RANGE, OklabPixel::B_RANGE], None),
+            ColourSpace::Oklch => ([OklchPixel::L_RANGE, OklchPixel::C_RANGE, OklchPixel::H_RANGE], Some(2)),
+        };
+
+        let values = values.map(|value| value.unwrap_or(f32::NAN));
+        for (index, (value, range)) in values.iter().zip(ranges).enumerate() {
+            let achromatic = hue == Some(index) && value.is_nan();
+            if !achromatic && !range.contains(value) {
+                return Err(ComponentOutOfRange { space, index, value: *value });
+            }
+        }
+
+        let components = (values[0], values[1], values[2]);
+        Ok(match space {
+            ColourSpace::Rgb => Colour::Rgb(components.into()),
+            ColourSpace::Hsl => Colour::Hsl(components.into()),
+            ColourSpace::Lab => Colour::Lab(components.into()),
+            ColourSpace::Lch => Colour::Lch(components.into()),
+            ColourSpace::Oklab => Colour::Oklab(components.into()),
+            ColourSpace::Oklch => Colour::Oklch(components.into()),
+        })
+    }
+}
+
+impl Colour {
+    /// Retrieves the colour space that the colour is currently in.
+    pub fn space(&self) -> ColourSpace {
+        match self {
+            Colour::Rgb(_) => ColourSpace::Rgb,
+            Colour::Hsl(_) => ColourSpace::Hsl,
+            Colour::Lab(_) => ColourSpace::Lab,
+            Colour::Lch(_) => ColourSpace::Lch,
+            Colour::Oklab(_) => ColourSpace::Oklab,
+            Colour::Oklch(_) => ColourSpace::Oklch,
+        }
+    }
+
+    /// Retrieves the raw components of the colour, in whichever space it's in.
+    pub fn get(&self) -> (f32, f32, f32) {
+        match self {
+            Colour::Rgb(pixel) => pixel.get(),
+            Colour::Hsl(pixel) => pixel.get(),
+            Colour::Lab(pixel) => pixel.get(),
+            Colour::Lch(pixel) => pixel.get(),
+            Colour::Oklab(pixel) => pixel.get(),
+            Colour::Oklch(pixel) => pixel.get(),
+        }
+    }
+
+    /// Converts the colour to an `RgbPixel`.
+    pub fn as_rgb(&self) -> RgbPixel {
+        match self {
+            Colour::Rgb(pixel) => *pixel,
+            Colour::Hsl(pixel) => pixel.as_rgb(),
+            Colour::Lab(pixel) => pixel.as_rgb(),
+            Colour::Lch(pixel) => pixel.as_rgb(),
+            Colour::Oklab(pixel) => pixel.as_rgb(),
+            Colour::Oklch(pixel) => pixel.as_rgb(),
+        }
+    }
+
+    /// Converts the colour to another colour space. Converting to the space the colour is
+    /// already in returns it unchanged.
+    ///
+    /// Conversions between LAB/LCH and OKLAB/OKLCH are done directly - anything else goes through RGB.
+    pub fn to(&self, space: ColourSpace) -> Colour {
+        if self.space() == space {
+            return *self;
+        }
+
+        match (self, space) {
+            (Colour::Lab(lab), ColourSpace::Lch) => Colour::Lch(lab.as_lch()),
+            (Colour::Lch(lch), ColourSpace::Lab) => Colour::Lab(lch.as_lab()),
+            (Colour::Oklab(oklab), ColourSpace::Oklch) => Colour::Oklch(oklab.as_oklch()),
+            (Colour::Oklch(oklch), ColourSpace::Oklab) => Colour::Oklab(oklch.as_oklab()),
+            _ => {
+                let rgb = self.as_rgb();
+                match space {
+                    ColourSpace::Rgb => Colour::Rgb(rgb),
+                    ColourSpace::Hsl => Colour::Hsl(rgb.as_hsl()),
+                    ColourSpace::Lab => Colour::Lab(rgb.as_lab()),
+                    ColourSpace::Lch => Colour::Lch(rgb.as_lch()),
+                    ColourSpace::Oklab => Colour::Oklab(rgb.as_oklab()),
+                    ColourSpace::Oklch => Colour::Oklch(rgb.as_oklch()),
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::pixel::{assert_close, lch::LchPixel, rgb::colours::RED};
+
+    use super::{Colour, ColourSpace};
+
+    #[test]
+    fn lch_to_rgb() {
+        let lch = RED.as_lch();
+        let colour = Colour::Lch(lch);
+
+        assert_eq!(colour.space(), ColourSpace::Lch);
+
+        let rgb = colour.to(ColourSpace::Rgb);
+        assert_eq!(rgb.space(), ColourSpace::Rgb);
+        assert_close(rgb.get(), RED.get(), 1e-3);
+        assert_close(colour.as_rgb().get(), RED.get(), 1e-3);
+    }
+
+    #[test]
+    fn converting_to_same_space_is_unchanged() {
+        let colour = Colour::from(LchPixel(50.0, 40.0, 120.0));
+
+        assert_close(colour.to(ColourSpace::Lch).get(), (50.0, 40.0, 120.0), 1e-3);
+    }
+
+    #[test]
+    fn round_trips_through_every_space() {
+        let colour = Colour::from(RED);
+
+        for space in [
+            ColourSpace::Hsl,
+            ColourSpace::Lab,
+            ColourSpace::Lch,
+            ColourSpace::Oklab,
+            ColourSpace::Oklch,
+        ] {
+            let converted = colour.to(space);
+            assert_eq!(converted.space(), space);
+            assert_close(converted.as_rgb().get(), RED.get(), 1e-3);
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trips_with_space() {
+        let colour = Colour::from(crate::pixel::oklch::OklchPixel(0.7, 0.15, 30.0));
+        let json = serde_json::to_string(&colour).unwrap();
+        assert_eq!(json, r#"{"space":"oklch","values":[0.7,0.15,30.0]}"#);
+
+        for space in [
+            ColourSpace::Rgb,
+            ColourSpace::Hsl,
+            ColourSpace::Lab,
+            ColourSpace::Lch,
+            ColourSpace::Oklab,
+            ColourSpace::Oklch,
+        ] {
+            let converted = Colour::from(RED).to(space);
+            let json = serde_json::to_string(&converted).unwrap();
+            let deserialized: Colour = serde_json::from_str(&json).unwrap();
+
+            assert_eq!(deserialized.space(), space);
+            assert_eq!(deserialized.get(), converted.get());
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trips_achromatic_hue() {
+        let grey = Colour::from(crate::pixel::rgb::RgbPixel(0.5, 0.5, 0.5)).to(ColourSpace::Oklch);
+        assert!(grey.get().2.is_nan());
+
+        let json = serde_json::to_string(&grey).unwrap();
+        assert!(json.ends_with(",null]}"), "{}", json);
+
+        let deserialized: Colour = serde_json::from_str(&json).unwrap();
+        assert_eq!(deserialized.space(), ColourSpace::Oklch);
+        assert_eq!(deserialized.get().0, grey.get().0);
+        assert!(deserialized.get().2.is_nan());
+
+        assert!(serde_json::from_str::<Colour>(r#"{"space":"oklch","values":[null,0.0,30.0]}"#).is_err());
+        assert!(serde_json::from_str::<Colour>(r#"{"space":"rgb","values":[0.5,null,0.5]}"#).is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_rejects_out_of_range() {
+        let result = serde_json::from_str::<Colour>(r#"{"space":"rgb","values":[1.5,0.0,0.0]}"#);
+        assert!(result.unwrap_err().to_string().contains("out of range"));
+
+        assert!(serde_json::from_str::<Colour>(r#"{"space":"lab","values":[50.0,0.0,-200.0]}"#).is_err());
+        assert!(serde_json::from_str::<Colour>(r#"{"space":"cmyk","values":[0.0,0.0,0.0]}"#).is_err());
+    }
+}
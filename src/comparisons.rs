@@ -24,6 +24,17 @@ pub fn cie76(lab_a: Colour, lab_b: Colour) -> f32 {
     ).sqrt()
 }
 
+/// Calculates the distance between two OKLAB colours using the euclidean distance function.
+/// 
+/// Since OKLAB is perceptually uniform, this works as a good ΔE without any extra weighting.
+pub fn delta_e_ok(oklab_a: Colour, oklab_b: Colour) -> f32 {
+    (
+          (oklab_b.0 - oklab_a.0).powi(2)
+        + (oklab_b.1 - oklab_a.1).powi(2)
+        + (oklab_b.2 - oklab_a.2).powi(2)
+    ).sqrt()
+}
+
 /// This function may not work correctly.
 pub fn cie94(lch_a: Colour, lch_b: Colour) -> f32 {
     const K_L: f32 = 1.0;
@@ -49,8 +60,8 @@ pub fn cie94(lch_a: Colour, lch_b: Colour) -> f32 {
 
 /// Calculates the distance between two LCH colours using CIEDE2000.
 /// 
-/// Not confirmed to be fully functional yet - however this algorithm is 
-/// proven to be the best, albeit significantly slower due to more computations.
+/// This algorithm is proven to be the best, albeit significantly slower due to more computations.
+/// Follows the formulae laid out by Sharma, Wu, and Dalal (2005).
 pub fn ciede2000(lch_a: Colour, lch_b: Colour) -> f32 {
     // set up constants for formula
     // these are usually unity (1)
@@ -66,11 +77,11 @@ pub fn ciede2000(lch_a: Colour, lch_b: Colour) -> f32 {
     let delta_l_mark = lch_b.0 - lch_a.0;
 
     let avg_l = (lch_b.0 + lch_a.0) / 2.0;
-    let avg_c = (lch_b.1 + lch_a.1) / 2.0;
+    let avg_c = ((a_1.powi(2) + b_1.powi(2)).sqrt() + (a_2.powi(2) + b_2.powi(2)).sqrt()) / 2.0;
 
-    let c_7_mul = 1.0 - (avg_c.powi(7) / (avg_c.powi(7) + 25_f32.powi(7)).sqrt());
-    let a_1_mark = a_1 + (a_1 / 2.0) * c_7_mul;
-    let a_2_mark = a_2 + (a_2 / 2.0) * c_7_mul;
+    let g = 0.5 * (1.0 - (avg_c.powi(7) / (avg_c.powi(7) + 25_f32.powi(7))).sqrt());
+    let a_1_mark = a_1 * (1.0 + g);
+    let a_2_mark = a_2 * (1.0 + g);
 
     let c_1_mark = (a_1_mark.powi(2) + b_1.powi(2)).sqrt();
     let c_2_mark = (a_2_mark.powi(2) + b_2.powi(2)).sqrt();
@@ -78,8 +89,13 @@ pub fn ciede2000(lch_a: Colour, lch_b: Colour) -> f32 {
     let delta_c_mark = c_2_mark - c_1_mark;
     let avg_c_mark = (c_2_mark + c_1_mark) / 2.0;
 
-    let h_1_mark = b_1.atan2(a_1_mark).to_degrees() % 360.0;
-    let h_2_mark = b_1.atan2(a_1_mark).to_degrees() % 360.0;
+    let hue_mark = |b: f32, a_mark: f32| if b == 0.0 && a_mark == 0.0 {
+        0.0
+    } else {
+        b.atan2(a_mark).to_degrees().rem_euclid(360.0)
+    };
+    let h_1_mark = hue_mark(b_1, a_1_mark);
+    let h_2_mark = hue_mark(b_2, a_2_mark);
 
     let abs_diff_h_marks = (h_1_mark - h_2_mark).abs();
     let delta_h_mark = 
@@ -87,7 +103,7 @@ pub fn ciede2000(lch_a: Colour, lch_b: Colour) -> f32 {
             0.0
         } else if abs_diff_h_marks <= 180.0 {
             h_2_mark - h_1_mark
-        } else if abs_diff_h_marks > 180.0 && h_2_mark <= h_1_mark {
+        } else if h_2_mark <= h_1_mark {
             h_2_mark - h_1_mark + 360.0
         } else {
             h_2_mark - h_1_mark - 360.0
@@ -99,7 +115,7 @@ pub fn ciede2000(lch_a: Colour, lch_b: Colour) -> f32 {
             h_1_mark + h_2_mark
         } else if abs_diff_h_marks <= 180.0 {
             (h_1_mark + h_2_mark) / 2.0
-        } else if abs_diff_h_marks > 180.0 && h_1_mark + h_2_mark < 360.0 {
+        } else if h_1_mark + h_2_mark < 360.0 {
             (h_1_mark + h_2_mark + 360.0) / 2.0
         } else {
             (h_1_mark + h_2_mark - 360.0) / 2.0
@@ -113,14 +129,15 @@ pub fn ciede2000(lch_a: Colour, lch_b: Colour) -> f32 {
 
     let s_l = 1.0
         + (0.015 * (avg_l - 50.0).powi(2))
-        / (20.0 + (avg_l - 50.0).powi(2));
+        / (20.0 + (avg_l - 50.0).powi(2)).sqrt();
 
     let s_c = 1.0 + 0.045 * avg_c_mark;
     let s_h = 1.0 + 0.015 * avg_c_mark * t;
 
+    let delta_theta = 30.0 * (-((avg_big_h_mark - 275.0) / 25.0).powi(2)).exp();
     let r_t = -2.0
         * (avg_c_mark.powi(7) / (avg_c_mark.powi(7) + 25_f32.powi(7))).sqrt()
-        * (60.0 * (-1.0 * ((avg_big_h_mark - 275.0) / 25.0).powi(2)).exp()).to_radians().sin();
+        * (2.0 * delta_theta).to_radians().sin();
 
     // the actual formula
     (
@@ -137,19 +154,40 @@ pub fn ciede2000(lch_a: Colour, lch_b: Colour) -> f32 {
 mod test {
     use std::time::Instant;
 
-    use crate::comparisons::{cie76, cie94, ciede2000};
+    use crate::comparisons::{cie76, cie94, ciede2000, delta_e_ok};
 
     use super::rgb_weighted_euclidean;
 
     const ITERATIONS: usize = 10_000;
     const TIME_SUFFIX: &'static str = "µs";
 
+    #[test]
+    fn ciede2000_matches_reference_data() {
+        use crate::conversions::lab_to_lch;
+
+        // pairs from Sharma, Wu, and Dalal's CIEDE2000 test data
+        let pairs = [
+            ((50.0, 2.6772, -79.7751), (50.0, 0.0, -82.7485), 2.0425),
+            ((50.0, -1.3802, -84.2814), (50.0, 0.0, -82.7485), 1.0000),
+            ((50.0, 2.5, 0.0), (73.0, 25.0, -18.0), 27.1492),
+            ((50.0, 2.5, 0.0), (50.0, 3.2592, 0.3350), 1.0000),
+            ((60.2574, -34.0099, 36.2677), (60.4626, -34.1751, 39.4387), 1.2644),
+            ((22.7233, 20.0904, -46.6940), (23.0331, 14.9730, -42.5619), 2.0373),
+        ];
+
+        for (lab_a, lab_b, expected) in pairs {
+            let distance = ciede2000(lab_to_lch(lab_a), lab_to_lch(lab_b));
+            assert!((distance - expected).abs() < 1e-3, "{} != {}", distance, expected);
+        }
+    }
+
     #[test]
     fn benchmarks() {
         benchmark_rgb_weighted_euclidean();
         benchmark_cie76();
         benchmark_cie94();
         benchmark_ciede2000();
+        benchmark_delta_e_ok();
     }
 
     fn benchmark_rgb_weighted_euclidean() {
@@ -191,4 +229,14 @@ mod test {
 
         println!("ciede2000: {}{}", now.elapsed().as_micros(), TIME_SUFFIX);
     }
+
+    fn benchmark_delta_e_ok() {
+        let now = Instant::now();
+
+        for _ in 1..ITERATIONS {
+            delta_e_ok((0.0, 0.0, 0.0), (0.0, 0.0, 0.0));
+        }
+
+        println!("delta_e_ok: {}{}", now.elapsed().as_micros(), TIME_SUFFIX);
+    }
 }
\ No newline at end of file
@@ -1,17 +1,48 @@
+/// The (x, y) chromaticity of the D50 white point.
+pub const D50_CHROMATICITY: (f32, f32) = (0.3457, 0.3585);
+
+/// The (x, y) chromaticity of the D65 white point.
+pub const D65_CHROMATICITY: (f32, f32) = (0.3127, 0.3290);
+
 /// Constants for D50 WHITE.
-const D50_WHITE: [f32; 3] = [
+pub const D50_WHITE: [f32; 3] = [
     0.3457 / 0.3585,
     1.00000,
     (1.0 - 0.3457 - 0.3585) / 0.3585,
 ];
 
 /// Constants for D65 WHITE.
-const D65_WHITE: [f32; 3] = [
+pub const D65_WHITE: [f32; 3] = [
     0.3127 / 0.3290,
     1.00000,
-    (1.0 - 0.3126 - 0.3290) / 0.3290,
+    (1.0 - 0.3127 - 0.3290) / 0.3290,
 ];
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// The reference white points used by this crate. LAB/LCH are relative to D50, while everything else is relative to D65.
+pub enum WhitePoint {
+    D50,
+    D65,
+}
+
+impl WhitePoint {
+    /// Retrieves the (x, y) chromaticity of the white point.
+    pub fn chromaticity(&self) -> (f32, f32) {
+        match self {
+            WhitePoint::D50 => D50_CHROMATICITY,
+            WhitePoint::D65 => D65_CHROMATICITY,
+        }
+    }
+}
+
+/// Retrieves the XYZ of a white point, with a luminance (Y) of `1.0`.
+pub fn white_point_xyz(wp: WhitePoint) -> [f32; 3] {
+    match wp {
+        WhitePoint::D50 => D50_WHITE,
+        WhitePoint::D65 => D65_WHITE,
+    }
+}
+
 // RGB -> HSL -> RGB
 
 /// Converts RGB to HSL.
@@ -100,9 +131,156 @@ pub fn hsl_to_rgb(hsl: (f32, f32, f32)) -> (f32, f32, f32) {
     )
 }
 
-// RGB -> XYZ_D65 -> RGB
+// RGB -> CMYK -> RGB
+
+/// Converts RGB to CMYK, naively - by inverting each channel, without generating any black.
+/// 
+/// This over-inks neutrals (greys are printed with all three inks), so `rgb_to_cmyk_ucr` is usually better for print.
+/// 
+/// The expected ranges for RGB are `(0.0~1.0, 0.0~1.0, 0.0~1.0)`
+/// 
+/// The returned CMYK values have the following ranges: `(0.0~1.0, 0.0~1.0, 0.0~1.0, 0.0~1.0)`.
+pub fn rgb_to_cmyk(rgb: (f32, f32, f32)) -> (f32, f32, f32, f32) {
+    (1.0 - rgb.0, 1.0 - rgb.1, 1.0 - rgb.2, 0.0)
+}
+
+/// Converts RGB to CMYK, replacing some of the coloured inks with black.
+/// 
+/// - `ucr` (under-colour removal) replaces the grey component of *neutral* colours with black.
+/// - `gcr` (grey-component replacement) replaces the grey component of *every* colour with black.
+/// 
+/// Both range from `0.0~1.0`, and the larger effect wins - so `gcr = 1.0` prints greys with pure black. The remaining
+/// inks are adjusted so the colour is kept (as far as `cmyk_to_rgb` is concerned). `ucr = 0.0, gcr = 0.0` is the same as `rgb_to_cmyk`.
+/// 
+/// The returned CMYK values have the following ranges: `(0.0~1.0, 0.0~1.0, 0.0~1.0, 0.0~1.0)`.
+pub fn rgb_to_cmyk_ucr(rgb: (f32, f32, f32), ucr: f32, gcr: f32) -> (f32, f32, f32, f32) {
+    let (c, m, y, _) = rgb_to_cmyk(rgb);
+
+    let grey = c.min(m).min(y);
+    let neutrality = 1.0 - (c.max(m).max(y) - grey);
+    let k = grey * gcr.max(ucr * neutrality).clamp(0.0, 1.0);
+
+    if k >= 1.0 {
+        return (0.0, 0.0, 0.0, 1.0);
+    }
+
+    let remove_black = |ink: f32| ((ink - k) / (1.0 - k)).max(0.0);
+    (remove_black(c), remove_black(m), remove_black(y), k)
+}
+
+// CMYK -> RGB
+
+/// Converts CMYK to RGB, naively - without any ink or paper profile.
+/// 
+/// The expected ranges for CMYK are `(0.0~1.0, 0.0~1.0, 0.0~1.0, 0.0~1.0)`
+/// 
+/// The returned RGB values have the following ranges: `(0.0~1.0, 0.0~1.0, 0.0~1.0)`.
+pub fn cmyk_to_rgb(cmyk: (f32, f32, f32, f32)) -> (f32, f32, f32) {
+    let (c, m, y, k) = cmyk;
+
+    (
+        (1.0 - c) * (1.0 - k),
+        (1.0 - m) * (1.0 - k),
+        (1.0 - y) * (1.0 - k),
+    )
+}
+
+// RGB -> YCBCR -> RGB
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// The standard matrices for converting between RGB and YCbCr - each weights the channels differently.
+pub enum YccMatrix {
+    /// ITU-R BT.601, for standard-definition video (and JPEG).
+    Bt601,
+    /// ITU-R BT.709, for high-definition video.
+    Bt709,
+}
+
+impl YccMatrix {
+    /// Retrieves the weights of red and blue in the luma (`Kr`, `Kb`) - green makes up the rest.
+    pub fn coefficients(&self) -> (f32, f32) {
+        match self {
+            YccMatrix::Bt601 => (0.299, 0.114),
+            YccMatrix::Bt709 => (0.2126, 0.0722),
+        }
+    }
+}
 
-/// Converts RGB to XYZ_D65.
+/// Converts RGB to (full-range) YCbCr - a luma, and two chroma differences centred on `0.5`.
+/// 
+/// The expected ranges for RGB are `(0.0~1.0, 0.0~1.0, 0.0~1.0)`
+/// 
+/// The returned YCbCr values have the following ranges: `(0.0~1.0, 0.0~1.0, 0.0~1.0)`.
+pub fn rgb_to_ycbcr(rgb: (f32, f32, f32), matrix: YccMatrix) -> (f32, f32, f32) {
+    let (r, g, b) = rgb;
+    let (kr, kb) = matrix.coefficients();
+
+    let y = kr * r + (1.0 - kr - kb) * g + kb * b;
+    let cb = (b - y) / (2.0 * (1.0 - kb)) + 0.5;
+    let cr = (r - y) / (2.0 * (1.0 - kr)) + 0.5;
+
+    (y, cb, cr)
+}
+
+/// Converts (full-range) YCbCr to RGB. See `rgb_to_ycbcr`.
+/// 
+/// The expected ranges for YCbCr are `(0.0~1.0, 0.0~1.0, 0.0~1.0)`
+/// 
+/// The returned RGB values have the following ranges: `(0.0~1.0, 0.0~1.0, 0.0~1.0)`.
+pub fn ycbcr_to_rgb(ycbcr: (f32, f32, f32), matrix: YccMatrix) -> (f32, f32, f32) {
+    let (y, cb, cr) = ycbcr;
+    let (kr, kb) = matrix.coefficients();
+    let kg = 1.0 - kr - kb;
+
+    let r = y + 2.0 * (1.0 - kr) * (cr - 0.5);
+    let b = y + 2.0 * (1.0 - kb) * (cb - 0.5);
+    let g = (y - kr * r - kb * b) / kg;
+
+    (r, g, b)
+}
+
+// SRGB (ENCODED) -> LINEAR RGB -> SRGB (ENCODED)
+
+/// Removes the sRGB transfer function ("gamma") from each channel - turning encoded sRGB (like the values
+/// stored in an image, and in an `RgbPixel`) into linear light, where values can be meaningfully added and averaged.
+/// 
+/// This is the first step from an `RgbPixel` to every other space - the XYZ matrices expect linear RGB.
+/// 
+/// The expected ranges for the channels are `0.0~1.0`. Negative values are mirrored.
+pub fn srgb_to_linear_rgb(rgb: (f32, f32, f32)) -> (f32, f32, f32) {
+    let decode = |channel: f32| {
+        let magnitude = channel.abs();
+        let linear = if magnitude <= 0.04045 {
+            magnitude / 12.92
+        } else {
+            ((magnitude + 0.055) / 1.055).powf(2.4)
+        };
+        linear.copysign(channel)
+    };
+
+    (decode(rgb.0), decode(rgb.1), decode(rgb.2))
+}
+
+/// Applies the sRGB transfer function to each channel - the inverse of `srgb_to_linear_rgb`.
+/// 
+/// The expected ranges for the channels are `0.0~1.0`. Negative values are mirrored.
+pub fn linear_rgb_to_srgb(rgb: (f32, f32, f32)) -> (f32, f32, f32) {
+    let encode = |channel: f32| {
+        let magnitude = channel.abs();
+        let encoded = if magnitude <= 0.0031308 {
+            magnitude * 12.92
+        } else {
+            1.055 * magnitude.powf(1.0 / 2.4) - 0.055
+        };
+        encoded.copysign(channel)
+    };
+
+    (encode(rgb.0), encode(rgb.1), encode(rgb.2))
+}
+
+// LINEAR RGB -> XYZ_D65 -> LINEAR RGB
+
+/// Converts linear RGB to XYZ_D65. Encoded sRGB (like an `RgbPixel`) needs `srgb_to_linear_rgb` first.
 /// 
 /// The expected ranges for RGB are `(0.0~1.0, 0.0~1.0, 0.0~1.0)`
 /// 
@@ -128,7 +306,7 @@ pub fn rgb_to_xyz_d65(rgb: (f32, f32, f32)) -> (f32, f32, f32) {
     (x, y, z)
 }
 
-/// Converts XYZ_D65 to RGB.
+/// Converts XYZ_D65 to linear RGB - which `linear_rgb_to_srgb` encodes for an `RgbPixel`.
 /// 
 /// The expected ranges for RGB are `(0.0~1.0, 0.0~1.0, 0.0~1.0)`
 pub fn xyz_d65_to_rgb(xyz: (f32, f32, f32)) -> (f32, f32, f32) {
@@ -152,6 +330,113 @@ pub fn xyz_d65_to_rgb(xyz: (f32, f32, f32)) -> (f32, f32, f32) {
     (r, g, b)
 }
 
+// RGB -> LMS -> RGB
+
+/// Converts linear RGB to the LMS cone responses (long, medium, and short wavelengths), using the matrix
+/// from Viénot, Brettel & Mollon (1999).
+/// 
+/// LMS is mostly useful for simulating colour vision deficiencies - each kind is missing one of the cones.
+pub fn rgb_to_lms(rgb: (f32, f32, f32)) -> (f32, f32, f32) {
+    let (r, g, b) = rgb;
+
+    let l = 
+        r * 17.8824 +
+        g * 43.5161 +
+        b * 4.11935;
+
+    let m = 
+        r * 3.45565 +
+        g * 27.1554 +
+        b * 3.86714;
+
+    let s = 
+        r * 0.0299566 +
+        g * 0.184309 +
+        b * 1.46709;
+
+    (l, m, s)
+}
+
+/// Converts LMS cone responses to linear RGB. See `rgb_to_lms`.
+pub fn lms_to_rgb(lms: (f32, f32, f32)) -> (f32, f32, f32) {
+    let (l, m, s) = lms;
+
+    let r = 
+        l *  0.08094445 +
+        m * -0.13050441 +
+        s *  0.11672107;
+
+    let g = 
+        l * -0.01024853 +
+        m *  0.05401933 +
+        s * -0.11361471;
+
+    let b = 
+        l * -0.0003652969 +
+        m * -0.0041216147 +
+        s *  0.6935114;
+
+    (r, g, b)
+}
+
+// XYZ_D65 -> DISPLAY_P3 -> XYZ_D65
+
+/// Converts XYZ_D65 to (linear) Display P3.
+/// 
+/// The returned Display P3 values have the following ranges: `(0.0~1.0, 0.0~1.0, 0.0~1.0)`.
+/// Display P3 is wider than RGB, so RGB colours will always be in range.
+pub fn xyz_d65_to_display_p3(xyz: (f32, f32, f32)) -> (f32, f32, f32) {
+    let (x, y, z) = xyz;
+
+    (
+        x *  2.493497 + y * -0.9313836 + z * -0.4027108,
+        x * -0.829489 + y * 1.7626641 + z *  0.023624687,
+        x *  0.03584583 + y * -0.07617239 + z * 0.9568845,
+    )
+}
+
+/// Converts (linear) Display P3 to XYZ_D65.
+/// 
+/// The expected ranges for Display P3 are `(0.0~1.0, 0.0~1.0, 0.0~1.0)`
+pub fn display_p3_to_xyz_d65(p3: (f32, f32, f32)) -> (f32, f32, f32) {
+    let (r, g, b) = p3;
+
+    (
+        r * 0.48657095 + g * 0.2656677 + b * 0.19821729,
+        r * 0.22897457 + g * 0.69173855 + b * 0.07928691,
+        r * 0.0 + g * 0.04511338 + b * 1.0439444,
+    )
+}
+
+// XYZ_D65 -> REC_2020 -> XYZ_D65
+
+/// Converts XYZ_D65 to (linear) Rec. 2020.
+/// 
+/// The returned Rec. 2020 values have the following ranges: `(0.0~1.0, 0.0~1.0, 0.0~1.0)`.
+/// Rec. 2020 is wider than both RGB and Display P3.
+pub fn xyz_d65_to_rec2020(xyz: (f32, f32, f32)) -> (f32, f32, f32) {
+    let (x, y, z) = xyz;
+
+    (
+        x *  1.7166512 + y * -0.35567078 + z * -0.2533663,
+        x * -0.6666843 + y *  1.6164812 + z *  0.015768547,
+        x *  0.017639857 + y * -0.042770613 + z *  0.94210315,
+    )
+}
+
+/// Converts (linear) Rec. 2020 to XYZ_D65.
+/// 
+/// The expected ranges for Rec. 2020 are `(0.0~1.0, 0.0~1.0, 0.0~1.0)`
+pub fn rec2020_to_xyz_d65(rec2020: (f32, f32, f32)) -> (f32, f32, f32) {
+    let (r, g, b) = rec2020;
+
+    (
+        r * 0.63695806 + g * 0.1446169 + b * 0.16888097,
+        r * 0.2627002 + g * 0.67799807 + b * 0.059301715,
+        r * 0.0 + g * 0.028072692 + b * 1.0609851,
+    )
+}
+
 // XYZ_D65 -> XYZ_D50 -> XYZ_D65
 
 /// Converts XYZ_D65 to XYZ_D50.
@@ -186,12 +471,42 @@ pub fn xyz_d50_to_xyz_d65(xyz_d50: (f32, f32, f32)) -> (f32, f32, f32) {
 /// 
 /// The returned LAB values have the following ranges: `(0.0~100.0, -125.0~125.0, -125.0~125.0)`
 pub fn xyz_d50_to_lab(xyz_d50: (f32, f32, f32)) -> (f32, f32, f32) {
+    xyz_to_lab_relative_to(xyz_d50, D50_WHITE)
+}
+
+/// Converts LAB to XYZ_D50.
+/// 
+/// The expected ranges for LAB are `(0.0~100.0, -125.0~125.0, -125.0~125.0)`
+/// 
+/// XYZ_D50 shouldn't be used as a colour, but as an intermediary between LAB and RGB.
+pub fn lab_to_xyz_d50(lab: (f32, f32, f32)) -> (f32, f32, f32) {
+    lab_to_xyz_relative_to(lab, D50_WHITE)
+}
+
+/// Converts XYZ_D65 to LAB relative to a D65 white - skipping the adaptation to D50.
+/// 
+/// Some pipelines (and some ΔE reference data) expect this - but it's *not* interchangeable with
+/// the (D50) LAB from `xyz_d50_to_lab`.
+/// 
+/// The returned LAB values have the following ranges: `(0.0~100.0, -125.0~125.0, -125.0~125.0)`
+pub fn xyz_d65_to_lab_d65(xyz_d65: (f32, f32, f32)) -> (f32, f32, f32) {
+    xyz_to_lab_relative_to(xyz_d65, D65_WHITE)
+}
+
+/// Converts LAB relative to a D65 white back to XYZ_D65.
+/// 
+/// The expected ranges for LAB are `(0.0~100.0, -125.0~125.0, -125.0~125.0)`
+pub fn lab_d65_to_xyz_d65(lab: (f32, f32, f32)) -> (f32, f32, f32) {
+    lab_to_xyz_relative_to(lab, D65_WHITE)
+}
+
+fn xyz_to_lab_relative_to(xyz: (f32, f32, f32), white: [f32; 3]) -> (f32, f32, f32) {
     const EPSILON: f32 = 216.0/24389.0;
     const K: f32 = 24389.0/27.0;
 
-    let (x, y, z) = xyz_d50;
+    let (x, y, z) = xyz;
     
-    let scale_to_white = |num: f32, i: usize| num / D50_WHITE[i];
+    let scale_to_white = |num: f32, i: usize| num / white[i];
     let (x, y, z) = (
         scale_to_white(x, 0),
         scale_to_white(y, 1),
@@ -213,12 +528,7 @@ pub fn xyz_d50_to_lab(xyz_d50: (f32, f32, f32)) -> (f32, f32, f32) {
     )
 }
 
-/// Converts LAB to XYZ_D50.
-/// 
-/// The expected ranges for LAB are `(0.0~100.0, -125.0~125.0, -125.0~125.0)`
-/// 
-/// XYZ_D50 shouldn't be used as a colour, but as an intermediary between LAB and RGB.
-pub fn lab_to_xyz_d50(lab: (f32, f32, f32)) -> (f32, f32, f32) {
+fn lab_to_xyz_relative_to(lab: (f32, f32, f32), white: [f32; 3]) -> (f32, f32, f32) {
     const EPSILON3: f32 = 24.0/116.0;
     const K: f32 = 24389.0/27.0;
 
@@ -233,7 +543,7 @@ pub fn lab_to_xyz_d50(lab: (f32, f32, f32)) -> (f32, f32, f32) {
         if f[2]    > EPSILON3 { f[2].powi(3)                   } else { (116.0 * f[2] - 16.0) / K},
     );
 
-    let scale_to_white = |num: f32, i: usize| num * D50_WHITE[i];
+    let scale_to_white = |num: f32, i: usize| num * white[i];
 
     (
         scale_to_white(x, 0),
@@ -292,6 +602,18 @@ pub fn lch_to_lab(lch: (f32, f32, f32)) -> (f32, f32, f32) {
 /// 
 /// The expected ranges for OKLAB are `(0.0~1.0, -0.4~0.4, -0.4~0.4)`
 pub fn xyz_d65_to_oklab(xyz_d65: (f32, f32, f32)) -> (f32, f32, f32) {
+    xyz_d65_to_oklab_using(xyz_d65, f32::cbrt)
+}
+
+/// Converts XYZ_D65 to OKLAB, like `xyz_d65_to_oklab` - but with an approximate cube root, trading a little
+/// accuracy for speed. Useful for bulk image processing, where the cube roots dominate.
+/// 
+/// Each component is within roughly `0.002` of the accurate conversion.
+pub fn xyz_d65_to_oklab_fast(xyz_d65: (f32, f32, f32)) -> (f32, f32, f32) {
+    xyz_d65_to_oklab_using(xyz_d65, fast_cbrt)
+}
+
+fn xyz_d65_to_oklab_using(xyz_d65: (f32, f32, f32), cbrt: impl Fn(f32) -> f32) -> (f32, f32, f32) {
     let (x, y, z) = xyz_d65;
 
     let lms = (
@@ -301,9 +623,9 @@ pub fn xyz_d65_to_oklab(xyz_d65: (f32, f32, f32)) -> (f32, f32, f32) {
     );
 
     let (l, m, s) = (
-        lms.0.cbrt(),
-        lms.1.cbrt(),
-        lms.2.cbrt(),
+        cbrt(lms.0),
+        cbrt(lms.1),
+        cbrt(lms.2),
     );
 
     (
@@ -382,14 +704,164 @@ pub fn oklch_to_oklab(oklch: (f32, f32, f32)) -> (f32, f32, f32) {
     (l, a, b)
 }
 
+// WHITE POINTS & ADAPTATION
+
+/// Approximates the (x, y) chromaticity of a black body at a correlated colour temperature (in kelvin).
+/// 
+/// Uses the cubic spline from Kim et al., which is valid between 1667K and 25000K - the temperature
+/// is clamped to that range.
+pub fn planckian_xy(cct: f32) -> (f32, f32) {
+    let t = cct.clamp(1667.0, 25000.0);
+
+    let x = if t <= 4000.0 {
+        -0.2661239e9 / t.powi(3) - 0.2343589e6 / t.powi(2) + 0.8776956e3 / t + 0.179910
+    } else {
+        -3.0258469e9 / t.powi(3) + 2.107038e6 / t.powi(2) + 0.2226347e3 / t + 0.240390
+    };
+
+    let y = if t <= 2222.0 {
+        -1.1063814 * x.powi(3) - 1.3481102 * x.powi(2) + 2.1855583 * x - 0.20219683
+    } else if t <= 4000.0 {
+        -0.9549476 * x.powi(3) - 1.3741859 * x.powi(2) + 2.09137 * x - 0.16748867
+    } else {
+        3.081758 * x.powi(3) - 5.873387 * x.powi(2) + 3.7511299 * x - 0.37001483
+    };
+
+    (x, y)
+}
+
+/// Converts (x, y) chromaticity coordinates to CIE 1960 (u, v) - where distances from the Planckian locus are measured.
+pub fn xy_to_uv(xy: (f32, f32)) -> (f32, f32) {
+    let (x, y) = xy;
+    let denominator = -2.0 * x + 12.0 * y + 3.0;
+
+    (4.0 * x / denominator, 6.0 * y / denominator)
+}
+
+/// Converts CIE 1960 (u, v) chromaticity coordinates back to (x, y).
+pub fn uv_to_xy(uv: (f32, f32)) -> (f32, f32) {
+    let (u, v) = uv;
+    let denominator = 2.0 * u - 8.0 * v + 4.0;
+
+    (3.0 * u / denominator, 2.0 * v / denominator)
+}
+
+/// Converts an (x, y) chromaticity to XYZ, with a luminance (Y) of `1.0`. Useful for building white points.
+pub fn xy_to_xyz(xy: (f32, f32)) -> (f32, f32, f32) {
+    let (x, y) = xy;
+
+    (x / y, 1.0, (1.0 - x - y) / y)
+}
+
+/// Adapts an XYZ colour from one white point to another, using a von Kries transform in the Bradford cone space.
+/// 
+/// A colour matching `source_white` will be mapped to `target_white`. Both whites are in XYZ.
+pub fn von_kries_adapt(xyz: (f32, f32, f32), source_white: (f32, f32, f32), target_white: (f32, f32, f32)) -> (f32, f32, f32) {
+    let to_lms = |(x, y, z): (f32, f32, f32)| (
+        x * 0.8951 + y * 0.2664 + z * -0.1614,
+        x * -0.7502 + y * 1.7135 + z * 0.0367,
+        x * 0.0389 + y * -0.0685 + z * 1.0296,
+    );
+
+    let (l, m, s) = to_lms(xyz);
+    let (source_l, source_m, source_s) = to_lms(source_white);
+    let (target_l, target_m, target_s) = to_lms(target_white);
+
+    let (l, m, s) = (
+        l * target_l / source_l,
+        m * target_m / source_m,
+        s * target_s / source_s,
+    );
+
+    (
+        l * 0.9869929 + m * -0.1470543 + s * 0.1599627,
+        l * 0.4323053 + m * 0.5183603 + s * 0.0492912,
+        l * -0.0085287 + m * 0.0400428 + s * 0.9684867,
+    )
+}
+
 // utils
 
+/// Averages a list of hues (in degrees) around the colour wheel, rather than along a line.
+/// 
+/// This means that the mean of `350.0` and `10.0` is `0.0` - not `180.0`. Achromatic hues (`NaN`) are
+/// skipped, and if there aren't any hues left, `NaN` is returned.
+/// 
+/// The returned hue ranges from `0.0~360.0`.
+pub fn circular_mean_hue(hues: &[f32]) -> f32 {
+    let (sin, cos) = hues
+        .iter()
+        .filter(|hue| !hue.is_nan())
+        .fold((0.0_f32, 0.0_f32), |(sin, cos), hue| {
+            (sin + hue.to_radians().sin(), cos + hue.to_radians().cos())
+        });
+
+    if sin == 0.0 && cos == 0.0 {
+        return f32::NAN;
+    }
+
+    sin.atan2(cos).to_degrees().rem_euclid(360.0)
+}
+
+/// Interpolates between two hues (in degrees) along the shortest arc - so going from `350.0` to `10.0`
+/// passes through `0.0`, rather than `180.0`.
+/// 
+/// If either hue is achromatic (`NaN`), the other one is used as-is. The returned hue ranges from `0.0~360.0`.
+pub fn lerp_hue(start: f32, end: f32, t: f32) -> f32 {
+    if start.is_nan() {
+        return end;
+    }
+    if end.is_nan() {
+        return start;
+    }
+
+    let delta = (end - start + 180.0).rem_euclid(360.0) - 180.0;
+    (start + delta * t).rem_euclid(360.0)
+}
+
+/// Snaps a hue (in degrees) to the nearest of `n` evenly-spaced hues, starting at `offset`. The distance is
+/// measured around the colour wheel - so with 6 hues, `350.0` snaps to `0.0` rather than `300.0`.
+/// 
+/// If `n` is `0` or the hue is achromatic (`NaN`), the hue is returned as-is. The returned hue ranges from `0.0~360.0`.
+pub fn snap_hue(hue: f32, n: u16, offset: f32) -> f32 {
+    if n == 0 || hue.is_nan() {
+        return hue;
+    }
+
+    let step = 360.0 / n as f32;
+    let steps_from_offset = ((hue - offset) / step).round();
+
+    (offset + steps_from_offset * step).rem_euclid(360.0)
+}
+
+/// Approximates the cube root of a number - with an initial guess from its bits, refined by a single Newton step.
+/// 
+/// The relative error is below `0.2%`. Zero, negative numbers, and subnormals are handled.
+pub fn fast_cbrt(value: f32) -> f32 {
+    if value == 0.0 || !value.is_finite() {
+        return value;
+    }
+
+    let magnitude = value.abs();
+    // the bit trick needs a normal exponent - so scale subnormals up by 2^24, whose cube root (2^8) is exact
+    if magnitude < f32::MIN_POSITIVE {
+        return (fast_cbrt(magnitude * 16_777_216.0) / 256.0).copysign(value);
+    }
+
+    // dividing the exponent by 3 (roughly) gives a guess within a few percent
+    let guess = f32::from_bits(magnitude.to_bits() / 3 + 0x2a51_4067);
+    let refined = (2.0 * guess + magnitude / (guess * guess)) / 3.0;
+
+    refined.copysign(value)
+}
+
 /// Allows conversions to be changed. This makes it more ergonomic to do some more complex conversions - such as RGB to LCH.
 /// 
 /// As an example, to go from RGB to LAB:
 /// 
 /// ```ignore
 /// chain_converstions((1.0, 0.0, 0.0), &[
+///     srgb_to_linear_rgb,
 ///     rgb_to_xyz_d65,
 ///     xyz_d65_to_xyz_d50,
 ///     xyz_d50_to_lab
@@ -401,4 +873,165 @@ pub fn chain_conversions(input: (f32, f32, f32), conversions: &[fn((f32, f32, f3
         result = func(result);
     }
     result
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Instant;
+
+    use super::{circular_mean_hue, snap_hue, cmyk_to_rgb, fast_cbrt, rgb_to_cmyk, rgb_to_cmyk_ucr, xyz_d65_to_oklab, xyz_d65_to_oklab_fast, lerp_hue, linear_rgb_to_srgb, srgb_to_linear_rgb, planckian_xy, uv_to_xy, von_kries_adapt, white_point_xyz, xy_to_uv, xy_to_xyz, xyz_d65_to_xyz_d50, WhitePoint, D65_CHROMATICITY};
+
+    #[test]
+    fn circular_mean_wraps_around() {
+        let mean = circular_mean_hue(&[350.0, 10.0]);
+        let distance_from_zero = mean.min(360.0 - mean);
+
+        assert!(distance_from_zero < 1e-3, "mean was {}", mean);
+        assert!((circular_mean_hue(&[80.0, 100.0, f32::NAN]) - 90.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn circular_mean_of_achromatic_is_nan() {
+        assert!(circular_mean_hue(&[f32::NAN, f32::NAN]).is_nan());
+        assert!(circular_mean_hue(&[]).is_nan());
+    }
+
+    #[test]
+    fn planckian_locus_passes_near_d65() {
+        // D65 sits slightly above the locus, so this is only approximate
+        let (x, y) = planckian_xy(6504.0);
+
+        assert!((x - 0.3127).abs() < 0.01 && (y - 0.3290).abs() < 0.01, "({}, {})", x, y);
+    }
+
+    #[test]
+    fn uv_round_trips() {
+        let (x, y) = uv_to_xy(xy_to_uv(D65_CHROMATICITY));
+
+        assert!((x - 0.3127).abs() < 1e-5 && (y - 0.3290).abs() < 1e-5);
+    }
+
+    #[test]
+    fn von_kries_maps_source_white_to_target() {
+        let source = xy_to_xyz(D65_CHROMATICITY);
+        let target = xy_to_xyz(planckian_xy(3000.0));
+        let (x, y, z) = von_kries_adapt(source, source, target);
+
+        assert!((x - target.0).abs() < 1e-4 && (y - target.1).abs() < 1e-4 && (z - target.2).abs() < 1e-4);
+    }
+
+    #[test]
+    fn lerp_hue_takes_shortest_arc() {
+        let midpoint = lerp_hue(350.0, 10.0, 0.5);
+
+        assert!(midpoint.min(360.0 - midpoint) < 1e-3, "midpoint was {}", midpoint);
+        assert!((lerp_hue(10.0, 350.0, 0.25) - 5.0).abs() < 1e-3);
+        assert_eq!(lerp_hue(f32::NAN, 120.0, 0.5), 120.0);
+    }
+
+    #[test]
+    fn d65_white_adapts_to_d50_white() {
+        let [x, y, z] = white_point_xyz(WhitePoint::D65);
+        let adapted = xyz_d65_to_xyz_d50((x, y, z));
+        let [x, y, z] = white_point_xyz(WhitePoint::D50);
+
+        assert!((adapted.0 - x).abs() < 1e-4 && (adapted.1 - y).abs() < 1e-4 && (adapted.2 - z).abs() < 1e-4, "{:?}", adapted);
+    }
+
+    #[test]
+    fn white_points_match_their_chromaticities() {
+        for wp in [WhitePoint::D50, WhitePoint::D65] {
+            let (x, y, z) = xy_to_xyz(wp.chromaticity());
+            assert_eq!([x, y, z], white_point_xyz(wp));
+        }
+    }
+
+    #[test]
+    fn srgb_transfer_round_trips() {
+        let (r, g, b) = srgb_to_linear_rgb((0.5, 0.02, 1.0));
+        assert!((r - 0.214).abs() < 1e-3 && (g - 0.02 / 12.92).abs() < 1e-6 && (b - 1.0).abs() < 1e-6);
+
+        let (r, g, b) = linear_rgb_to_srgb((r, g, b));
+        assert!((r - 0.5).abs() < 1e-5 && (g - 0.02).abs() < 1e-5 && (b - 1.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn fast_cbrt_is_close() {
+        let mut value = 1e-6_f32;
+        while value < 1e6 {
+            for sample in [value, -value] {
+                let relative_error = ((fast_cbrt(sample) - sample.cbrt()) / sample.cbrt()).abs();
+                assert!(relative_error < 2e-3, "cbrt({}) was off by {}", sample, relative_error);
+            }
+            value *= 1.37;
+        }
+
+        for subnormal in [f32::MIN_POSITIVE / 2.0, 1e-40, 1e-44, f32::from_bits(1)] {
+            for sample in [subnormal, -subnormal] {
+                let relative_error = ((fast_cbrt(sample) - sample.cbrt()) / sample.cbrt()).abs();
+                assert!(relative_error < 2e-3, "cbrt({}) was off by {}", sample, relative_error);
+            }
+        }
+
+        assert_eq!(fast_cbrt(0.0), 0.0);
+    }
+
+    #[test]
+    fn fast_oklab_is_close() {
+        for xyz in [(0.9505, 1.0, 1.0891), (0.4124, 0.2126, 0.0193), (0.1, 0.05, 0.3), (0.01, 0.01, 0.01)] {
+            let (accurate, fast) = (xyz_d65_to_oklab(xyz), xyz_d65_to_oklab_fast(xyz));
+
+            assert!((accurate.0 - fast.0).abs() < 2e-3, "{:?} vs {:?}", accurate, fast);
+            assert!((accurate.1 - fast.1).abs() < 2e-3, "{:?} vs {:?}", accurate, fast);
+            assert!((accurate.2 - fast.2).abs() < 2e-3, "{:?} vs {:?}", accurate, fast);
+        }
+    }
+
+    #[test]
+    fn benchmark_oklab_fast() {
+        const ITERATIONS: usize = 100_000;
+        const TIME_SUFFIX: &str = "µs";
+
+        let now = Instant::now();
+        for i in 1..ITERATIONS {
+            std::hint::black_box(xyz_d65_to_oklab(std::hint::black_box((i as f32 / ITERATIONS as f32, 0.5, 0.5))));
+        }
+        println!("xyz_d65_to_oklab: {}{}", now.elapsed().as_micros(), TIME_SUFFIX);
+
+        let now = Instant::now();
+        for i in 1..ITERATIONS {
+            std::hint::black_box(xyz_d65_to_oklab_fast(std::hint::black_box((i as f32 / ITERATIONS as f32, 0.5, 0.5))));
+        }
+        println!("xyz_d65_to_oklab_fast: {}{}", now.elapsed().as_micros(), TIME_SUFFIX);
+    }
+
+    #[test]
+    fn gcr_prints_greys_with_black() {
+        let (c, m, y, k) = rgb_to_cmyk_ucr((0.4, 0.4, 0.4), 0.0, 1.0);
+        assert!(c.abs() < 1e-6 && m.abs() < 1e-6 && y.abs() < 1e-6, "({}, {}, {})", c, m, y);
+        assert!((k - 0.6).abs() < 1e-6);
+
+        let (c, m, y, k) = rgb_to_cmyk_ucr((0.4, 0.4, 0.4), 1.0, 0.0);
+        assert!(c.abs() < 1e-6 && m.abs() < 1e-6 && y.abs() < 1e-6 && (k - 0.6).abs() < 1e-6);
+
+        assert_eq!(rgb_to_cmyk_ucr((0.4, 0.4, 0.4), 0.0, 0.0), rgb_to_cmyk((0.4, 0.4, 0.4)));
+    }
+
+    #[test]
+    fn cmyk_round_trips() {
+        for rgb in [(0.8, 0.4, 0.2), (0.1, 0.5, 0.9), (0.3, 0.3, 0.35), (0.0, 0.0, 0.0)] {
+            for (ucr, gcr) in [(0.0, 0.0), (1.0, 0.0), (0.5, 0.5), (0.0, 1.0)] {
+                let (r, g, b) = cmyk_to_rgb(rgb_to_cmyk_ucr(rgb, ucr, gcr));
+                assert!((r - rgb.0).abs() < 1e-5 && (g - rgb.1).abs() < 1e-5 && (b - rgb.2).abs() < 1e-5, "{:?} with ({}, {})", rgb, ucr, gcr);
+            }
+        }
+    }
+
+    #[test]
+    fn snap_hue_wraps_around() {
+        assert_eq!(snap_hue(350.0, 6, 0.0), 0.0);
+        assert_eq!(snap_hue(95.0, 6, 0.0), 120.0);
+        assert_eq!(snap_hue(95.0, 6, 30.0), 90.0);
+        assert_eq!(snap_hue(95.0, 0, 30.0), 95.0);
+    }
+}
@@ -0,0 +1,181 @@
+use crate::{conversions::{linear_rgb_to_srgb, lms_to_rgb, rgb_to_lms, srgb_to_linear_rgb}, pixel::rgb::{DeltaEMethod, RgbPixel}};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// The kinds of colour vision deficiency (CVD) that can be simulated - each is missing one kind of cone.
+pub enum CvdType {
+    /// No long-wavelength (red) cones.
+    Protanopia,
+    /// No medium-wavelength (green) cones.
+    Deuteranopia,
+    /// No short-wavelength (blue) cones.
+    Tritanopia,
+}
+
+impl CvdType {
+    /// Every kind of deficiency, in declaration order.
+    pub const ALL: [CvdType; 3] = [CvdType::Protanopia, CvdType::Deuteranopia, CvdType::Tritanopia];
+
+    /// Replaces the missing cone's response with one predicted from the other two - so colours that only
+    /// differ in the missing cone become identical.
+    fn project(&self, lms: (f32, f32, f32)) -> (f32, f32, f32) {
+        let (l, m, s) = lms;
+
+        match self {
+            CvdType::Protanopia => (2.02344 * m - 2.52581 * s, m, s),
+            CvdType::Deuteranopia => (l, 0.494207 * l + 1.24827 * s, s),
+            CvdType::Tritanopia => (l, m, -0.395913 * l + 0.801109 * m),
+        }
+    }
+
+    /// The direction in LMS that the deficient observer can't see - the missing cone.
+    fn confusion_axis(&self) -> (f32, f32, f32) {
+        match self {
+            CvdType::Protanopia => (1.0, 0.0, 0.0),
+            CvdType::Deuteranopia => (0.0, 1.0, 0.0),
+            CvdType::Tritanopia => (0.0, 0.0, 1.0),
+        }
+    }
+}
+
+/// Simulates how a colour looks to someone with a colour vision deficiency, using the method from
+/// Viénot, Brettel & Mollon (1999).
+///
+/// The cones respond to light, so the pixel is decoded to linear light first - and the result is re-encoded.
+/// Whites, greys, and blacks look the same to every observer.
+pub fn simulate_cvd(pixel: &RgbPixel, kind: CvdType) -> RgbPixel {
+    let lms = rgb_to_lms(srgb_to_linear_rgb(pixel.get()));
+    linear_rgb_to_srgb(lms_to_rgb(kind.project(lms))).into()
+}
+
+/// Retrieves `samples` colours that look the same as `pixel` to someone with the given deficiency - its confusion line.
+///
+/// The line is straight in linear light, so the colours are evenly spaced (in linear light) along the missing
+/// cone's axis, from one edge of the RGB gamut to the other - and each is clamped to it before being encoded.
+/// If no part of the line is within the gamut, no colours are returned.
+pub fn confusion_line(pixel: &RgbPixel, kind: CvdType, samples: usize) -> Vec<RgbPixel> {
+    let origin = srgb_to_linear_rgb(pixel.get());
+    let axis = lms_to_rgb(kind.confusion_axis());
+
+    // the range of steps along the axis that keeps every channel within 0~1
+    let mut range = (f32::NEG_INFINITY, f32::INFINITY);
+    for (value, direction) in [(origin.0, axis.0), (origin.1, axis.1), (origin.2, axis.2)] {
+        if direction == 0.0 {
+            if !(0.0..=1.0).contains(&value) {
+                return vec![];
+            }
+            continue;
+        }
+
+        let (a, b) = ((0.0 - value) / direction, (1.0 - value) / direction);
+        range = (range.0.max(a.min(b)), range.1.min(a.max(b)));
+    }
+
+    if range.0 > range.1 {
+        return vec![];
+    }
+
+    let step = |index: usize| match samples {
+        1 => (range.0 + range.1) / 2.0,
+        _ => range.0 + (range.1 - range.0) * index as f32 / (samples - 1) as f32,
+    };
+
+    (0..samples)
+        .map(|index| {
+            let t = step(index);
+            let linear = RgbPixel(origin.0 + axis.0 * t, origin.1 + axis.1 * t, origin.2 + axis.2 * t).clamp();
+            RgbPixel::from(linear_rgb_to_srgb(linear.get()))
+        })
+        .collect()
+}
+
+/// Checks that every colour in `palette` stays distinguishable under each kind of deficiency - that is,
+/// every pair of simulated colours is more than `min_delta_e` apart (by CIEDE2000).
+///
+/// Otherwise, returns the first pair (as indices into `palette`) that got too close, and the deficiency
+/// that did it. Each deficiency is checked in the order of `CvdType::ALL`.
+pub fn is_cvd_safe(palette: &[RgbPixel], min_delta_e: f32) -> Result<(), (usize, usize, CvdType)> {
+    for kind in CvdType::ALL {
+        let simulated: Vec<RgbPixel> = palette.iter().map(|colour| simulate_cvd(colour, kind)).collect();
+
+        for (i, a) in simulated.iter().enumerate() {
+            for (j, b) in simulated.iter().enumerate().skip(i + 1) {
+                let distance = a.delta_e(b, DeltaEMethod::Ciede2000);
+                if distance.is_nan() || distance <= min_delta_e {
+                    return Err((i, j, kind));
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::{confusion_line, is_cvd_safe, simulate_cvd, CvdType};
+    use crate::pixel::rgb::{colours, DeltaEMethod, MixSpace, RgbPixel};
+
+    #[test]
+    fn greys_look_the_same() {
+        for kind in [CvdType::Protanopia, CvdType::Deuteranopia, CvdType::Tritanopia] {
+            let (r, g, b) = simulate_cvd(&colours::WHITE, kind).get();
+            assert!((r - 1.0).abs() < 1e-3 && (g - 1.0).abs() < 1e-3 && (b - 1.0).abs() < 1e-3);
+        }
+    }
+
+    #[test]
+    fn confusion_line_simulates_to_one_colour() {
+        let pixel = RgbPixel(0.6, 0.4, 0.3);
+
+        for kind in [CvdType::Protanopia, CvdType::Deuteranopia, CvdType::Tritanopia] {
+            let line = confusion_line(&pixel, kind, 8);
+            assert_eq!(line.len(), 8);
+
+            let expected = simulate_cvd(&pixel, kind);
+            for colour in line.iter() {
+                let (r, g, b) = simulate_cvd(colour, kind).get();
+                let (er, eg, eb) = expected.get();
+                assert!((r - er).abs() < 1e-3 && (g - eg).abs() < 1e-3 && (b - eb).abs() < 1e-3, "{:?} under {:?}", colour, kind);
+            }
+
+            // ...while actually being different colours
+            assert!(line.first().unwrap().distance_from(line.last().unwrap()) > 0.01);
+        }
+    }
+
+    #[test]
+    fn confusion_line_is_straight_in_linear_light() {
+        let pixel = RgbPixel(0.6, 0.4, 0.3);
+
+        for kind in CvdType::ALL {
+            let line = confusion_line(&pixel, kind, 2);
+            let midpoint = MixSpace::LinearRgb.lerp(&line[0], &line[1], 0.5);
+
+            let (r, g, b) = simulate_cvd(&midpoint, kind).get();
+            let (er, eg, eb) = simulate_cvd(&pixel, kind).get();
+            assert!((r - er).abs() < 1e-3 && (g - eg).abs() < 1e-3 && (b - eb).abs() < 1e-3, "{:?} under {:?}", midpoint, kind);
+        }
+    }
+
+    #[test]
+    fn no_line_outside_gamut() {
+        assert!(confusion_line(&RgbPixel(-1.0, -1.0, -1.0), CvdType::Protanopia, 8).is_empty());
+    }
+
+    #[test]
+    fn red_green_is_not_cvd_safe() {
+        let palette = [colours::BLACK, RgbPixel(0.8, 0.3, 0.2), RgbPixel(0.1, 0.59, 0.18)];
+
+        // clearly different to everyone else...
+        assert!(palette[1].delta_e(&palette[2], DeltaEMethod::Ciede2000) > 40.0);
+        assert_eq!(is_cvd_safe(&palette, 10.0), Err((1, 2, CvdType::Deuteranopia)));
+    }
+
+    #[test]
+    fn blue_orange_is_cvd_safe() {
+        let palette = [colours::BLACK, colours::WHITE, RgbPixel(0.1, 0.3, 0.9), RgbPixel(0.95, 0.6, 0.1)];
+
+        assert_eq!(is_cvd_safe(&palette, 10.0), Ok(()));
+        assert_eq!(is_cvd_safe(&palette[..1], 10.0), Ok(()));
+    }
+}
@@ -0,0 +1,178 @@
+use crate::pixel::{oklab::OklabPixel, rgb::{colours, RgbPixel}};
+
+#[derive(Debug, Clone, Copy)]
+/// A colour at a position (from `0.0` to `1.0`) along a gradient.
+pub struct GradientStop {
+    pub position: f32,
+    pub colour: RgbPixel,
+}
+
+#[derive(Debug, Clone, Default)]
+/// A gradient between any number of colour stops.
+///
+/// The stops are always sorted, and their positions normalized so the first is at `0.0` and the last at `1.0`.
+/// Colours between the stops are interpolated in OKLAB.
+pub struct Gradient {
+    stops: Vec<GradientStop>,
+}
+
+impl Gradient {
+    /// Creates a gradient from a list of stops. The stops are sorted, and their positions stretched to fill `0.0~1.0`.
+    pub fn new(mut stops: Vec<GradientStop>) -> Gradient {
+        stops.sort_by(|a, b| a.position.total_cmp(&b.position));
+
+        let (first, last) = match (stops.first(), stops.last()) {
+            (Some(first), Some(last)) => (first.position, last.position),
+            _ => return Gradient { stops },
+        };
+
+        let span = last - first;
+        for stop in stops.iter_mut() {
+            stop.position = if span > 0.0 { (stop.position - first) / span } else { 0.0 };
+        }
+
+        Gradient { stops }
+    }
+
+    /// Creates a gradient with the colours evenly spaced.
+    pub fn even(colours: &[RgbPixel]) -> Gradient {
+        let step = 1.0 / colours.len().saturating_sub(1).max(1) as f32;
+
+        Gradient::new(
+            colours
+                .iter()
+                .enumerate()
+                .map(|(i, colour)| GradientStop { position: i as f32 * step, colour: *colour })
+                .collect(),
+        )
+    }
+
+    pub fn stops(&self) -> &[GradientStop] {
+        &self.stops
+    }
+
+    /// Retrieves the colour at a position along the gradient. Positions before the first stop (or after the
+    /// last) are the colour of that stop. An empty gradient is black.
+    pub fn sample(&self, position: f32) -> RgbPixel {
+        let (first, last) = match (self.stops.first(), self.stops.last()) {
+            (Some(first), Some(last)) => (first, last),
+            _ => return colours::BLACK,
+        };
+
+        if position <= first.position {
+            return first.colour;
+        }
+        if position >= last.position {
+            return last.colour;
+        }
+
+        self.stops
+            .windows(2)
+            .find(|pair| position <= pair[1].position)
+            .map(|pair| {
+                let (start, end) = (pair[0], pair[1]);
+                let span = end.position - start.position;
+                if position >= end.position || span <= 0.0 {
+                    return end.colour;
+                }
+
+                let t = (position - start.position) / span;
+                OklabPixel::lerp(&start.colour.as_oklab(), &end.colour.as_oklab(), t).as_rgb()
+            })
+            .unwrap_or(last.colour)
+    }
+
+    /// Reverses the gradient, so it runs from the last colour to the first.
+    #[must_use]
+    pub fn reversed(&self) -> Gradient {
+        Gradient {
+            stops: self
+                .stops
+                .iter()
+                .rev()
+                .map(|stop| GradientStop { position: 1.0 - stop.position, colour: stop.colour })
+                .collect(),
+        }
+    }
+
+    /// Joins two gradients together - this one is squeezed into the front half, and `other` into the back half.
+    #[must_use]
+    pub fn concat(&self, other: &Gradient) -> Gradient {
+        let front = self.stops.iter().map(|stop| GradientStop { position: stop.position * 0.5, ..*stop });
+        let back = other.stops.iter().map(|stop| GradientStop { position: 0.5 + stop.position * 0.5, ..*stop });
+
+        Gradient::new(front.chain(back).collect())
+    }
+
+    /// Repeats the gradient `times` times, squeezing each repetition to fit. A `times` of `0` is treated as `1`.
+    #[must_use]
+    pub fn repeat(&self, times: usize) -> Gradient {
+        let times = times.max(1);
+        let scale = 1.0 / times as f32;
+
+        Gradient::new(
+            (0..times)
+                .flat_map(|i| {
+                    self.stops.iter().map(move |stop| GradientStop {
+                        position: (i as f32 + stop.position) * scale,
+                        colour: stop.colour,
+                    })
+                })
+                .collect(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::pixel::rgb::colours::{BLACK, BLUE, RED, WHITE};
+
+    use super::{Gradient, GradientStop};
+
+    #[test]
+    fn new_normalizes_stops() {
+        let gradient = Gradient::new(vec![
+            GradientStop { position: 30.0, colour: BLUE },
+            GradientStop { position: 10.0, colour: RED },
+            GradientStop { position: 20.0, colour: WHITE },
+        ]);
+
+        let positions: Vec<f32> = gradient.stops().iter().map(|stop| stop.position).collect();
+        assert_eq!(positions, vec![0.0, 0.5, 1.0]);
+        assert_eq!(gradient.sample(0.0).get(), RED.get());
+        assert_eq!(gradient.sample(1.0).get(), BLUE.get());
+    }
+
+    #[test]
+    fn reversed_swaps_endpoints() {
+        let reversed = Gradient::even(&[RED, WHITE, BLUE]).reversed();
+
+        assert_eq!(reversed.sample(0.0).get(), BLUE.get());
+        assert_eq!(reversed.sample(1.0).get(), RED.get());
+        assert_eq!(reversed.stops()[1].position, 0.5);
+    }
+
+    #[test]
+    fn concat_passes_through_both() {
+        let gradient = Gradient::even(&[RED, WHITE]).concat(&Gradient::even(&[BLACK, BLUE]));
+        let positions: Vec<f32> = gradient.stops().iter().map(|stop| stop.position).collect();
+
+        assert_eq!(positions, vec![0.0, 0.5, 0.5, 1.0]);
+        assert_eq!(gradient.sample(0.0).get(), RED.get());
+        assert_eq!(gradient.sample(0.5).get(), WHITE.get());
+        assert_eq!(gradient.sample(1.0).get(), BLUE.get());
+    }
+
+    #[test]
+    fn repeat_has_pattern_twice() {
+        let gradient = Gradient::even(&[RED, BLUE]).repeat(2);
+        let stops = gradient.stops();
+
+        assert_eq!(stops.len(), 4);
+        assert_eq!(stops.iter().map(|stop| stop.position).collect::<Vec<f32>>(), vec![0.0, 0.5, 0.5, 1.0]);
+        assert_eq!(stops[0].colour.get(), RED.get());
+        assert_eq!(stops[1].colour.get(), BLUE.get());
+        assert_eq!(stops[2].colour.get(), RED.get());
+        assert_eq!(stops[3].colour.get(), BLUE.get());
+    }
+}
@@ -0,0 +1,91 @@
+use palette::{white_point::D50, Lab, LinSrgb, Oklab, Srgb};
+
+use crate::pixel::{lab::LabPixel, linear_rgb::LinearRgbPixel, oklab::OklabPixel, rgb::RgbPixel};
+
+/// `RgbPixel` is encoded sRGB, so it maps onto `palette`'s `Srgb` as-is.
+impl From<Srgb<f32>> for RgbPixel {
+    fn from(value: Srgb<f32>) -> Self {
+        RgbPixel(value.red, value.green, value.blue)
+    }
+}
+
+/// `RgbPixel` is encoded sRGB, so it maps onto `palette`'s `Srgb` as-is.
+impl From<RgbPixel> for Srgb<f32> {
+    fn from(value: RgbPixel) -> Self {
+        Srgb::new(value.0, value.1, value.2)
+    }
+}
+
+/// `LinearRgbPixel` maps onto `palette`'s `LinSrgb` as-is.
+impl From<LinSrgb<f32>> for LinearRgbPixel {
+    fn from(value: LinSrgb<f32>) -> Self {
+        LinearRgbPixel(value.red, value.green, value.blue)
+    }
+}
+
+/// `LinearRgbPixel` maps onto `palette`'s `LinSrgb` as-is.
+impl From<LinearRgbPixel> for LinSrgb<f32> {
+    fn from(value: LinearRgbPixel) -> Self {
+        LinSrgb::new(value.0, value.1, value.2)
+    }
+}
+
+/// `LabPixel` is relative to D50, so it maps onto `Lab<D50>` - rather than `palette`'s default `Lab` (which is D65).
+impl From<Lab<D50, f32>> for LabPixel {
+    fn from(value: Lab<D50, f32>) -> Self {
+        LabPixel(value.l, value.a, value.b)
+    }
+}
+
+/// `LabPixel` is relative to D50, so it maps onto `Lab<D50>` - rather than `palette`'s default `Lab` (which is D65).
+impl From<LabPixel> for Lab<D50, f32> {
+    fn from(value: LabPixel) -> Self {
+        Lab::new(value.0, value.1, value.2)
+    }
+}
+
+impl From<Oklab<f32>> for OklabPixel {
+    fn from(value: Oklab<f32>) -> Self {
+        OklabPixel(value.l, value.a, value.b)
+    }
+}
+
+impl From<OklabPixel> for Oklab<f32> {
+    fn from(value: OklabPixel) -> Self {
+        Oklab::new(value.0, value.1, value.2)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use palette::{chromatic_adaptation::AdaptIntoUnclamped, white_point::{D50, D65}, FromColor, Lab, LinSrgb, Oklab, Srgb, Xyz};
+
+    use crate::pixel::{assert_close, lab::LabPixel, linear_rgb::LinearRgbPixel, oklab::OklabPixel, rgb::RgbPixel};
+
+    #[test]
+    fn rgb_round_trips() {
+        let pixel = RgbPixel(0.8, 0.4, 0.2);
+
+        let encoded = Srgb::from(pixel);
+        assert_eq!((encoded.red, encoded.green, encoded.blue), pixel.get());
+        assert_eq!(RgbPixel::from(Srgb::new(0.5, 0.25, 1.0)).get(), (0.5, 0.25, 1.0));
+
+        let linear = LinSrgb::from(LinearRgbPixel::from_rgb(&pixel));
+        assert_close(LinearRgbPixel::from(linear).get(), LinearRgbPixel::from_rgb(&pixel).get(), 1e-6);
+        assert_close(LinearRgbPixel::from(encoded.into_linear::<f32>()).get(), LinearRgbPixel::from_rgb(&pixel).get(), 1e-5);
+    }
+
+    #[test]
+    fn lab_and_oklab_agree_with_palette() {
+        let linear = LinearRgbPixel::from_rgb(&RgbPixel(0.8, 0.4, 0.2));
+        let (lab_pixel, oklab_pixel) = (LabPixel::from_linear_rgb(&linear), OklabPixel::from_linear_rgb(&linear));
+
+        let xyz_d50: Xyz<D50, f32> = Xyz::<D65, f32>::from_color(LinSrgb::from(linear)).adapt_into_unclamped();
+        let lab = Lab::<D50, f32>::from_color(xyz_d50);
+        assert_close(LabPixel::from(lab).get(), lab_pixel.get(), 0.05);
+        assert_close(Lab::<D50, f32>::from(lab_pixel).into_components(), lab.into_components(), 0.05);
+
+        let oklab = Oklab::from_color(LinSrgb::from(linear));
+        assert_close(OklabPixel::from(oklab).get(), oklab_pixel.get(), 1e-3);
+    }
+}
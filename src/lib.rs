@@ -1,6 +1,45 @@
+/// The pixel types - one for each colour space. Unless its docs say otherwise, each pixel's `Default` is black
+/// (every component zeroed), rather than an error value.
+///
+/// `RgbPixel` is *encoded* sRGB (like the bytes of an image), so converting it to any other space removes the
+/// transfer function first, and converting back applies it. For linear light, use `LinearRgbPixel`.
+///
+/// This is a breaking change - `RgbPixel` used to go straight into the XYZ matrix, as if it were linear. Colours
+/// that relied on that should be converted with `LinearRgbPixel::as_rgb` first.
 pub mod pixel;
 pub mod comparisons;
 
+/// A dynamically-typed `Colour` that wraps each of the pixel types, and remembers which space it's in.
+pub mod colour;
+
+/// Utilities for working with palettes (lists of colours) - such as extracting them from an image.
+pub mod palette;
+
+/// Gradients between any number of colour stops - which can be reversed, joined, and repeated.
+pub mod gradient;
+
+/// Analysing images (lists of pixels) - such as histograms of their lightness and hue.
+pub mod analysis;
+
+/// Building photo mosaics - averaging tiles, and finding the closest tile for a colour.
+pub mod mosaic;
+
+/// The named CSS colours - and finding the closest name for any colour.
+pub mod named;
+
+/// Simulating colour vision deficiencies (colour blindness) - and finding the colours they confuse.
+pub mod cvd;
+
+/// Parsing colours from strings - with errors, rather than falling back to black.
+pub mod parse;
+
+/// Converting spectral measurements (spectral power distributions) into colours.
+pub mod spectral;
+
+// Conversions between this crate's pixels and the `palette` crate's colours - documented on each impl.
+#[cfg(feature = "palette")]
+mod interop;
+
 /// The raw conversion algorithms between multiple colour schemes. 
 /// 
 /// Implementation is inspired by `color.js` - especially the [spaces](https://github.com/LeaVerou/color.js/tree/main/src/spaces)
@@ -10,7 +49,7 @@ pub mod comparisons;
 /// As an example, here are all the steps to convert RGB to LCH:
 /// 
 /// ```text
-/// RGB -> XYZ_D65 -> XYZ_D50 -> LAB -> LCH
+/// RGB -> LINEAR RGB -> XYZ_D65 -> XYZ_D50 -> LAB -> LCH
 /// ```
 /// 
 /// Instead, you could just use the `.as_lch()` method on an `RgbPixel` to do this for you.
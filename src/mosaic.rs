@@ -0,0 +1,114 @@
+use crate::{comparisons::ciede2000, conversions::{circular_mean_hue, linear_rgb_to_srgb, srgb_to_linear_rgb}, pixel::{lab::LabPixel, lch::LchPixel, rgb::{colours, MixSpace, RgbPixel}}};
+
+/// Averages the colour of a tile (any list of pixels) in the given space. An empty tile is black.
+///
+/// The channels are summed in a single pass, so every space other than `MixSpace::Rgb` only needs
+/// a conversion per pixel - and only one conversion back at the end. In `MixSpace::CieLch`, the hue
+/// is averaged around the colour wheel.
+pub fn average_tile(pixels: &[RgbPixel], space: MixSpace) -> RgbPixel {
+    if pixels.is_empty() {
+        return colours::BLACK;
+    }
+
+    let count = pixels.len() as f32;
+    let sum = |convert: fn(&RgbPixel) -> (f32, f32, f32)| {
+        let (r, g, b) = pixels
+            .iter()
+            .map(convert)
+            .fold((0.0, 0.0, 0.0), |acc, value| (acc.0 + value.0, acc.1 + value.1, acc.2 + value.2));
+        (r / count, g / count, b / count)
+    };
+
+    match space {
+        MixSpace::Rgb => sum(|pixel| pixel.get()).into(),
+        MixSpace::LinearRgb => linear_rgb_to_srgb(sum(|pixel| srgb_to_linear_rgb(pixel.get()))).into(),
+        MixSpace::Oklab => RgbPixel::perceptual_mean(pixels),
+        MixSpace::CieLab => LabPixel::from(sum(|pixel| pixel.as_lab().get())).as_rgb(),
+        MixSpace::CieLch => {
+            let (lightness, chroma, _) = sum(|pixel| pixel.as_lch().get());
+            let hues: Vec<f32> = pixels.iter().map(|pixel| pixel.as_lch().2).collect();
+            LchPixel(lightness, chroma, circular_mean_hue(&hues)).as_rgb()
+        }
+    }
+}
+
+/// Finds the closest tile for a colour - the core of building a photo mosaic.
+///
+/// The tiles' colours (usually from `average_tile`) are converted once up-front, and compared
+/// with CIEDE2000.
+pub struct MosaicIndex {
+    tiles: Vec<LchPixel>,
+}
+
+impl MosaicIndex {
+    pub fn new(tiles: &[RgbPixel]) -> MosaicIndex {
+        MosaicIndex {
+            tiles: tiles.iter().map(RgbPixel::as_lch).collect(),
+        }
+    }
+
+    /// The number of tiles in the index.
+    pub fn len(&self) -> usize {
+        self.tiles.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tiles.is_empty()
+    }
+
+    /// Retrieves the index of the tile closest to `target`. Returns `None` if there aren't any tiles.
+    pub fn nearest(&self, target: &RgbPixel) -> Option<usize> {
+        let target = target.as_lch().get();
+
+        self.tiles
+            .iter()
+            .map(|tile| ciede2000(target, tile.get()))
+            .enumerate()
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(index, _)| index)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::pixel::rgb::{colours, MixSpace, RgbPixel};
+
+    use super::{average_tile, MosaicIndex};
+
+    #[test]
+    fn averages_in_each_space() {
+        let tile = [colours::BLACK, colours::WHITE];
+
+        assert_eq!(average_tile(&tile, MixSpace::Rgb).get(), (0.5, 0.5, 0.5));
+
+        // linear light is brighter than the raw average, since it's re-encoded
+        let (linear, _, _) = average_tile(&tile, MixSpace::LinearRgb).get();
+        assert!((linear - 0.735).abs() < 1e-2, "{}", linear);
+
+        let (oklab, _, _) = average_tile(&tile, MixSpace::Oklab).get();
+        assert!(oklab > 0.0 && oklab < 0.5, "{}", oklab);
+
+        assert_eq!(average_tile(&[], MixSpace::Rgb).get(), (0.0, 0.0, 0.0));
+
+        for space in [MixSpace::CieLab, MixSpace::CieLch] {
+            let (grey, _, _) = average_tile(&tile, space).get();
+            assert!(grey > 0.0 && grey < 0.5, "{}", grey);
+        }
+    }
+
+    #[test]
+    fn finds_nearest_tile() {
+        let tiles = [
+            average_tile(&[colours::RED, colours::ORANGE], MixSpace::LinearRgb),
+            average_tile(&[colours::BLUE, colours::CYAN], MixSpace::LinearRgb),
+            average_tile(&[colours::BLACK], MixSpace::LinearRgb),
+        ];
+        let index = MosaicIndex::new(&tiles);
+
+        assert_eq!(index.len(), 3);
+        assert_eq!(index.nearest(&RgbPixel(0.9, 0.3, 0.1)), Some(0));
+        assert_eq!(index.nearest(&RgbPixel(0.1, 0.4, 0.9)), Some(1));
+        assert_eq!(index.nearest(&RgbPixel(0.05, 0.05, 0.05)), Some(2));
+        assert_eq!(MosaicIndex::new(&[]).nearest(&colours::RED), None);
+    }
+}
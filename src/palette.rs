@@ -0,0 +1,809 @@
+use crate::{comparisons::ciede2000, conversions::cmyk_to_rgb, pixel::{lab::LabPixel, oklch::OklchPixel, rgb::{DeltaEMethod, Gamut, RgbPixel}, rgba::RgbaPixel}};
+
+/// The golden angle in degrees - stepping the hue by this amount never lands on a previous hue.
+pub const GOLDEN_ANGLE: f32 = 137.508;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// The ways that reading a palette file can fail.
+pub enum PaletteError {
+    /// The file didn't start with the expected header.
+    InvalidHeader,
+    /// The file ended partway through.
+    UnexpectedEnd,
+    /// A colour used a colour model that isn't supported.
+    UnsupportedColourModel(String),
+    /// The file had a block of a type that isn't known.
+    UnknownBlock(u16),
+    /// The file had this many bytes left over after the last colour.
+    TrailingBytes(usize),
+    /// The file declared a different number of colours than it contained.
+    CountMismatch { declared: usize, found: usize },
+    /// A colour couldn't be read.
+    InvalidColour(String),
+}
+
+impl std::fmt::Display for PaletteError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PaletteError::InvalidHeader => write!(f, "invalid palette header"),
+            PaletteError::UnexpectedEnd => write!(f, "unexpected end of palette"),
+            PaletteError::UnsupportedColourModel(model) => write!(f, "unsupported colour model: {:?}", model),
+            PaletteError::UnknownBlock(block_type) => write!(f, "unknown block type: {:#06x}", block_type),
+            PaletteError::TrailingBytes(count) => write!(f, "{} unexpected bytes after the palette", count),
+            PaletteError::CountMismatch { declared, found } => write!(f, "expected {} colours, found {}", declared, found),
+            PaletteError::InvalidColour(colour) => write!(f, "invalid colour: {:?}", colour),
+        }
+    }
+}
+
+impl std::error::Error for PaletteError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// An error for when two lists that should line up (one value per pixel) have different lengths.
+/// Like `PaletteError::CountMismatch`, but for lists passed in rather than read from a file.
+pub struct LengthMismatch {
+    /// The length of the first list - which the second should match.
+    pub expected: usize,
+    /// The length of the second list.
+    pub found: usize,
+}
+
+impl std::fmt::Display for LengthMismatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "expected {} values (one per pixel), found {}", self.expected, self.found)
+    }
+}
+
+impl std::error::Error for LengthMismatch {}
+
+#[derive(Debug, Clone)]
+/// A palette that's already been converted into the space of a distance metric - so quantizing many pixels
+/// against it only converts each pixel, rather than the whole palette every time. See `RgbPixel::quantize_perceptual`.
+pub struct PreparedPalette {
+    palette: Vec<RgbPixel>,
+    prepared: Vec<(f32, f32, f32)>,
+    metric: DeltaEMethod,
+}
+
+impl PreparedPalette {
+    /// Converts each colour in the palette into the space of `metric`.
+    pub fn new(palette: &[RgbPixel], metric: DeltaEMethod) -> PreparedPalette {
+        PreparedPalette {
+            palette: palette.to_vec(),
+            prepared: palette.iter().map(|colour| metric.prepare(colour)).collect(),
+            metric,
+        }
+    }
+
+    /// Retrieves the original colours of the palette.
+    pub fn palette(&self) -> &[RgbPixel] {
+        &self.palette
+    }
+
+    /// Retrieves the metric the palette was prepared for.
+    pub fn metric(&self) -> DeltaEMethod {
+        self.metric
+    }
+
+    /// Retrieves the index of the nearest colour to `pixel` - or `None` if the palette is empty.
+    ///
+    /// Distances are measured from the pixel, like `RgbPixel::delta_e` (CIE94 isn't symmetric). If multiple
+    /// colours are equally near, the first one is picked. A `NaN` distance (which CIE94 gives for greys, whose
+    /// hue is `NaN`) counts as infinitely far - so it's only picked if every distance is `NaN`.
+    pub fn quantize_index(&self, pixel: &RgbPixel) -> Option<usize> {
+        let pixel = self.metric.prepare(pixel);
+        let distance = |colour: &(f32, f32, f32)| match self.metric.distance(pixel, *colour) {
+            distance if distance.is_nan() => f32::INFINITY,
+            distance => distance,
+        };
+
+        self.prepared
+            .iter()
+            .map(distance)
+            .enumerate()
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(index, _)| index)
+    }
+
+    /// Quantizes `pixel` to the nearest colour in the palette. An empty palette returns the pixel as-is.
+    pub fn quantize(&self, pixel: &RgbPixel) -> RgbPixel {
+        match self.quantize_index(pixel) {
+            Some(index) => self.palette[index],
+            None => *pixel,
+        }
+    }
+}
+
+/// Extracts a palette of (at most) `size` colours from a list of pixels, using median cut.
+///
+/// The pixels are split into buckets along whichever channel has the widest range, until there are
+/// `size` buckets. Each bucket is then averaged into a single colour. Fewer colours are returned if
+/// there aren't enough distinct pixels to split.
+pub fn extract_palette(pixels: &[RgbPixel], size: usize) -> Vec<RgbPixel> {
+    if pixels.is_empty() || size == 0 {
+        return Vec::new();
+    }
+
+    let mut buckets = vec![pixels.to_vec()];
+
+    while buckets.len() < size {
+        let widest = buckets
+            .iter()
+            .enumerate()
+            .map(|(i, bucket)| (i, widest_channel(bucket)))
+            .filter(|(_, (_, range))| *range > 0.0)
+            .max_by(|a, b| a.1.1.total_cmp(&b.1.1));
+
+        let Some((index, (channel, _))) = widest else {
+            break;
+        };
+
+        let mut bucket = buckets.swap_remove(index);
+        bucket.sort_by(|a, b| channel_value(a, channel).total_cmp(&channel_value(b, channel)));
+        let upper = bucket.split_off(bucket.len() / 2);
+
+        buckets.push(bucket);
+        buckets.push(upper);
+    }
+
+    buckets.iter().map(|bucket| average(bucket)).collect()
+}
+
+/// Extracts a palette from a list of RGBA pixels - same as `extract_palette`, except pixels with an
+/// alpha below `alpha_threshold` are skipped.
+///
+/// Transparent pixels still have (arbitrary) RGB values, which would otherwise pull the palette
+/// towards colours that can't actually be seen.
+pub fn extract_palette_rgba(pixels: &[RgbaPixel], size: usize, alpha_threshold: f32) -> Vec<RgbPixel> {
+    let opaque: Vec<RgbPixel> = pixels
+        .iter()
+        .filter(|pixel| pixel.alpha() >= alpha_threshold)
+        .map(|pixel| pixel.rgb())
+        .collect();
+
+    extract_palette(&opaque, size)
+}
+
+/// Generates `n` colours with well-separated hues, by stepping the hue by the golden angle each time.
+///
+/// `saturation` and `lightness` are the OKLCH chroma and lightness used for every colour, and the
+/// first colour has a hue of `start_hue`. Each colour is gamut-mapped to RGB.
+pub fn golden_palette(n: usize, saturation: f32, lightness: f32, start_hue: f32) -> Vec<RgbPixel> {
+    (0..n)
+        .map(|i| {
+            let hue = (start_hue + i as f32 * GOLDEN_ANGLE).rem_euclid(360.0);
+            OklchPixel(lightness, saturation, hue).gamut_map(Gamut::Srgb)
+        })
+        .collect()
+}
+
+/// Removes near-duplicate colours from a palette - keeping each colour only if it's more than `min_delta_e`
+/// (CIEDE2000) away from every colour kept before it.
+///
+/// The order of the palette is kept, so the first of any near-duplicates is the one that survives.
+pub fn dedup_palette(palette: &[RgbPixel], min_delta_e: f32) -> Vec<RgbPixel> {
+    let mut kept: Vec<(RgbPixel, (f32, f32, f32))> = Vec::new();
+
+    for colour in palette {
+        let lch = colour.as_lch().get();
+        if kept.iter().all(|(_, other)| ciede2000(lch, *other) > min_delta_e) {
+            kept.push((*colour, lch));
+        }
+    }
+
+    kept.into_iter().map(|(colour, _)| colour).collect()
+}
+
+/// Quantizes an image (a list of pixels, `width` pixels per row) to a palette - dithering with Floyd-Steinberg
+/// error diffusion, so that areas between palette colours are approximated by a mix of them.
+///
+/// Pixels where the `mask` is `true` are masked out - they're quantized flat (without dithering), and error
+/// is never diffused into or out of them. This keeps regions like UI overlays clean, without their edges
+/// bleeding into the rest of the image. The mask must have exactly one value per pixel.
+pub fn quantize_image(pixels: &[RgbPixel], width: usize, palette: &[RgbPixel], mask: Option<&[bool]>) -> Result<Vec<RgbPixel>, LengthMismatch> {
+    if let Some(mask) = mask.filter(|mask| mask.len() != pixels.len()) {
+        return Err(LengthMismatch { expected: pixels.len(), found: mask.len() });
+    }
+
+    let is_masked = |index: usize| mask.is_some_and(|mask| mask[index]);
+    let mut errors = vec![(0.0, 0.0, 0.0); pixels.len()];
+    let mut quantized = Vec::with_capacity(pixels.len());
+
+    for (index, pixel) in pixels.iter().enumerate() {
+        if is_masked(index) {
+            quantized.push(pixel.quantize(palette));
+            continue;
+        }
+
+        let error: (f32, f32, f32) = errors[index];
+        let value = RgbPixel(pixel.0 + error.0, pixel.1 + error.1, pixel.2 + error.2);
+        let nearest = value.quantize(palette);
+        let error = (value.0 - nearest.0, value.1 - nearest.1, value.2 - nearest.2);
+        quantized.push(nearest);
+
+        let (x, y) = (index % width.max(1), index / width.max(1));
+        let neighbours = [(1, 0, 7.0), (-1, 1, 3.0), (0, 1, 5.0), (1, 1, 1.0)];
+
+        for (dx, dy, weight) in neighbours {
+            let nx = x as isize + dx;
+            if nx < 0 || nx >= width as isize {
+                continue;
+            }
+
+            let neighbour = (y + dy) * width + nx as usize;
+            if neighbour < pixels.len() && !is_masked(neighbour) {
+                let weight = weight / 16.0;
+                let target = &mut errors[neighbour];
+                *target = (target.0 + error.0 * weight, target.1 + error.1 * weight, target.2 + error.2 * weight);
+            }
+        }
+    }
+
+    Ok(quantized)
+}
+
+/// Quantizes every pixel to the index of its nearest palette colour (see `RgbPixel::quantize_index`), along
+/// with how many pixels used each palette colour - in the same order as the palette.
+///
+/// The counts are the feedback for refining a palette - such as dropping unused colours, or weighting
+/// common ones. An empty palette can't quantize anything, so both lists are empty.
+pub fn quantize_indices_weighted(pixels: &[RgbPixel], palette: &[RgbPixel]) -> (Vec<usize>, Vec<usize>) {
+    if palette.is_empty() {
+        return (vec![], vec![]);
+    }
+
+    let mut counts = vec![0; palette.len()];
+    let indices = pixels
+        .iter()
+        .map(|pixel| {
+            let index = pixel.quantize_index(palette).expect("the palette isn't empty");
+            counts[index] += 1;
+            index
+        })
+        .collect();
+
+    (indices, counts)
+}
+
+/// Measures how well an image was quantized - as the mean distance between each original pixel and its
+/// quantized counterpart. Useful for comparing palette sizes or dithering modes.
+///
+/// An empty image has no error.
+pub fn quantization_error(original: &[RgbPixel], quantized: &[RgbPixel], metric: DeltaEMethod) -> Result<f32, LengthMismatch> {
+    let errors = quantization_errors(original, quantized, metric)?;
+    if original.is_empty() {
+        return Ok(0.0);
+    }
+
+    Ok(errors.sum::<f32>() / original.len() as f32)
+}
+
+/// Like `quantization_error`, but retrieves the worst (maximum) distance instead of the mean.
+pub fn max_quantization_error(original: &[RgbPixel], quantized: &[RgbPixel], metric: DeltaEMethod) -> Result<f32, LengthMismatch> {
+    Ok(quantization_errors(original, quantized, metric)?.fold(0.0, f32::max))
+}
+
+fn quantization_errors<'a>(original: &'a [RgbPixel], quantized: &'a [RgbPixel], metric: DeltaEMethod) -> Result<impl Iterator<Item = f32> + 'a, LengthMismatch> {
+    if original.len() != quantized.len() {
+        return Err(LengthMismatch { expected: original.len(), found: quantized.len() });
+    }
+
+    Ok(original.iter().zip(quantized).map(move |(a, b)| a.delta_e(b, metric)))
+}
+
+/// Reads the colours from an Adobe Swatch Exchange (`.ase`) file.
+///
+/// RGB, CMYK, Gray, and LAB colours are supported - CMYK and Gray are converted naively, and LAB is
+/// assumed to be relative to D50 (as it is in the rest of this crate). Groups are flattened, so
+/// every colour in the file is returned in order. Any other kind of block is rejected.
+pub fn from_ase(bytes: &[u8]) -> Result<Vec<RgbPixel>, PaletteError> {
+    const GROUP_START: u16 = 0xc001;
+    const GROUP_END: u16 = 0xc002;
+    const COLOUR_ENTRY: u16 = 0x0001;
+
+    let mut reader = ByteReader { bytes, position: 0 };
+
+    if reader.take(4)? != b"ASEF" {
+        return Err(PaletteError::InvalidHeader);
+    }
+    // version (major, minor)
+    reader.take(4)?;
+    let block_count = reader.read_u32()?;
+
+    let mut colours = Vec::new();
+    for _ in 0..block_count {
+        let block_type = reader.read_u16()?;
+        let block_length = reader.read_u32()? as usize;
+        let mut block = ByteReader { bytes: reader.take(block_length)?, position: 0 };
+
+        match block_type {
+            COLOUR_ENTRY => {
+                // the name is a null-terminated UTF-16 string, prefixed with its length in code units
+                let name_length = block.read_u16()? as usize;
+                block.take(name_length * 2)?;
+
+                let model = block.take(4)?;
+                let colour = match model {
+                    b"RGB " => RgbPixel(block.read_f32()?, block.read_f32()?, block.read_f32()?),
+                    b"CMYK" => cmyk_to_rgb((block.read_f32()?, block.read_f32()?, block.read_f32()?, block.read_f32()?)).into(),
+                    b"Gray" => {
+                        let grey = block.read_f32()?;
+                        RgbPixel(grey, grey, grey)
+                    }
+                    b"LAB " => LabPixel(block.read_f32()? * 100.0, block.read_f32()?, block.read_f32()?).as_rgb(),
+                    _ => return Err(PaletteError::UnsupportedColourModel(String::from_utf8_lossy(model).into_owned())),
+                };
+                colours.push(colour);
+            }
+            GROUP_START | GROUP_END => {}
+            _ => return Err(PaletteError::UnknownBlock(block_type)),
+        }
+    }
+
+    Ok(colours)
+}
+
+/// The magic bytes at the start of the binary format from `palette_to_bytes`.
+const BINARY_MAGIC: &[u8; 4] = b"CXPL";
+
+/// Packs a palette into a compact binary format - a header (the magic bytes `CXPL`, then the number of
+/// colours as a big-endian `u32`), followed by 3 bytes per colour. Read it back with `palette_from_bytes`.
+///
+/// Each colour is stored with `get_u8`, so anything more precise than 8 bits per channel is rounded off.
+pub fn palette_to_bytes(palette: &[RgbPixel]) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(BINARY_MAGIC.len() + 4 + palette.len() * 3);
+    bytes.extend_from_slice(BINARY_MAGIC);
+    bytes.extend_from_slice(&(palette.len() as u32).to_be_bytes());
+
+    for colour in palette {
+        let (r, g, b) = colour.get_u8();
+        bytes.extend_from_slice(&[r, g, b]);
+    }
+
+    bytes
+}
+
+/// Reads a palette from the binary format written by `palette_to_bytes`.
+///
+/// Errors if the header is wrong, or if there are fewer (or more) colours than the header says.
+pub fn palette_from_bytes(bytes: &[u8]) -> Result<Vec<RgbPixel>, PaletteError> {
+    let mut reader = ByteReader { bytes, position: 0 };
+
+    if reader.take(BINARY_MAGIC.len())? != BINARY_MAGIC {
+        return Err(PaletteError::InvalidHeader);
+    }
+    let count = reader.read_u32()? as usize;
+
+    let colours = reader
+        .take(count.checked_mul(3).ok_or(PaletteError::UnexpectedEnd)?)?
+        .chunks_exact(3)
+        .map(|channels| RgbPixel::from((channels[0], channels[1], channels[2])))
+        .collect();
+
+    match bytes.len() - reader.position {
+        0 => Ok(colours),
+        remaining => Err(PaletteError::TrailingBytes(remaining)),
+    }
+}
+
+/// The header lines at the start of a JASC-PAL file - the format, then its version.
+const JASC_HEADER: [&str; 2] = ["JASC-PAL", "0100"];
+
+/// Writes a palette in the JASC-PAL text format (originally from Paint Shop Pro) - the header, the number of colours,
+/// then a line of `r g b` (from `0` to `255`) per colour. Lines end in `\r\n`, as in the original format.
+///
+/// Each colour is stored with `get_u8`, so anything more precise than 8 bits per channel is rounded off.
+pub fn to_jasc_pal(palette: &[RgbPixel]) -> String {
+    let mut lines = vec![JASC_HEADER[0].to_string(), JASC_HEADER[1].to_string(), palette.len().to_string()];
+    lines.extend(palette.iter().map(|colour| {
+        let (r, g, b) = colour.get_u8();
+        format!("{} {} {}", r, g, b)
+    }));
+
+    lines.iter().map(|line| format!("{}\r\n", line)).collect()
+}
+
+/// Reads a palette from the JASC-PAL text format. See `to_jasc_pal`.
+///
+/// Either line ending is accepted, as is surrounding whitespace and blank lines after the colours.
+/// Errors if the header is wrong, if a colour isn't 3 numbers from `0` to `255`, or if the number of
+/// colours doesn't match the one declared in the header.
+pub fn from_jasc_pal(text: &str) -> Result<Vec<RgbPixel>, PaletteError> {
+    let mut lines = text.lines().map(str::trim);
+
+    for expected in JASC_HEADER {
+        if lines.next() != Some(expected) {
+            return Err(PaletteError::InvalidHeader);
+        }
+    }
+    let declared: usize = lines.next().and_then(|count| count.parse().ok()).ok_or(PaletteError::InvalidHeader)?;
+
+    let colours = lines
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            let channels: Result<Vec<u8>, _> = line.split_whitespace().map(str::parse).collect();
+            match channels.as_deref() {
+                Ok(&[r, g, b]) => Ok(RgbPixel::from((r, g, b))),
+                _ => Err(PaletteError::InvalidColour(line.to_string())),
+            }
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    match colours.len() {
+        found if found == declared => Ok(colours),
+        found => Err(PaletteError::CountMismatch { declared, found }),
+    }
+}
+
+/// Reads big-endian values from a byte slice, erroring instead of panicking when it runs out.
+struct ByteReader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> ByteReader<'a> {
+    fn take(&mut self, count: usize) -> Result<&'a [u8], PaletteError> {
+        let end = self.position.checked_add(count).ok_or(PaletteError::UnexpectedEnd)?;
+        let taken = self.bytes.get(self.position..end).ok_or(PaletteError::UnexpectedEnd)?;
+        self.position = end;
+        Ok(taken)
+    }
+
+    fn read_u16(&mut self) -> Result<u16, PaletteError> {
+        let bytes = self.take(2)?;
+        Ok(u16::from_be_bytes([bytes[0], bytes[1]]))
+    }
+
+    fn read_u32(&mut self) -> Result<u32, PaletteError> {
+        let bytes = self.take(4)?;
+        Ok(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    fn read_f32(&mut self) -> Result<f32, PaletteError> {
+        Ok(f32::from_bits(self.read_u32()?))
+    }
+}
+
+/// Retrieves the channel (0, 1, or 2) with the widest range in the bucket, and that range.
+fn widest_channel(bucket: &[RgbPixel]) -> (usize, f32) {
+    (0..3)
+        .map(|channel| {
+            let (min, max) = bucket.iter().fold((f32::MAX, f32::MIN), |(min, max), pixel| {
+                let value = channel_value(pixel, channel);
+                (min.min(value), max.max(value))
+            });
+            (channel, max - min)
+        })
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+        .unwrap_or((0, 0.0))
+}
+
+fn channel_value(pixel: &RgbPixel, channel: usize) -> f32 {
+    match channel {
+        0 => pixel.0,
+        1 => pixel.1,
+        _ => pixel.2,
+    }
+}
+
+fn average(bucket: &[RgbPixel]) -> RgbPixel {
+    let count = bucket.len() as f32;
+    let (r, g, b) = bucket
+        .iter()
+        .fold((0.0, 0.0, 0.0), |acc, pixel| (acc.0 + pixel.0, acc.1 + pixel.1, acc.2 + pixel.2));
+
+    RgbPixel(r / count, g / count, b / count)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::pixel::{rgb::{colours::{self, BLUE, GREEN, RED}, DeltaEMethod, Gamut, RgbPixel}, rgba::RgbaPixel};
+
+    use super::{dedup_palette, extract_palette, extract_palette_rgba, from_ase, from_jasc_pal, golden_palette, max_quantization_error, palette_from_bytes, palette_to_bytes, quantization_error, quantize_image, quantize_indices_weighted, to_jasc_pal, LengthMismatch, PaletteError, PreparedPalette, GOLDEN_ANGLE};
+
+    #[test]
+    fn extracts_distinct_colours() {
+        let pixels = [RED, RED, BLUE, BLUE, RED, BLUE];
+        let palette = extract_palette(&pixels, 2);
+
+        assert_eq!(palette.len(), 2);
+        assert!(palette.iter().any(|colour| colour.get() == RED.get()));
+        assert!(palette.iter().any(|colour| colour.get() == BLUE.get()));
+    }
+
+    #[test]
+    fn transparent_pixels_are_ignored() {
+        // the top half is opaque red/blue, the bottom half is transparent green
+        let mut image = Vec::new();
+        for i in 0..32 {
+            let colour = if i % 2 == 0 { RED } else { BLUE };
+            image.push(RgbaPixel::from(colour));
+        }
+        for _ in 0..32 {
+            image.push(RgbaPixel(GREEN.0, GREEN.1, GREEN.2, 0.0));
+        }
+
+        let palette = extract_palette_rgba(&image, 2, 0.5);
+
+        assert_eq!(palette.len(), 2);
+        for colour in palette.iter() {
+            assert_eq!(colour.1, 0.0, "{:?} was pulled towards green", colour);
+        }
+    }
+
+    #[test]
+    fn golden_palette_steps_by_golden_angle() {
+        let palette = golden_palette(8, 0.1, 0.7, 30.0);
+
+        assert_eq!(palette.len(), 8);
+        for pair in palette.windows(2) {
+            let step = (pair[1].as_oklch().2 - pair[0].as_oklch().2).rem_euclid(360.0);
+            assert!((step - GOLDEN_ANGLE).abs() < 1.0, "stepped by {}", step);
+        }
+    }
+
+    #[test]
+    fn golden_palette_is_in_gamut() {
+        let palette = golden_palette(16, 0.4, 0.6, 0.0);
+
+        assert!(palette.iter().all(|colour| colour.is_in_gamut(Gamut::Srgb)));
+    }
+
+    /// Builds an ASE colour entry block with a single-character name.
+    fn ase_colour(model: &[u8; 4], values: &[f32]) -> Vec<u8> {
+        let mut body = vec![0, 2, 0, b'a', 0, 0];
+        body.extend_from_slice(model);
+        for value in values {
+            body.extend_from_slice(&value.to_be_bytes());
+        }
+        // colour type (normal)
+        body.extend_from_slice(&2_u16.to_be_bytes());
+
+        let mut block = 1_u16.to_be_bytes().to_vec();
+        block.extend_from_slice(&(body.len() as u32).to_be_bytes());
+        block.extend(body);
+        block
+    }
+
+    fn ase_file(blocks: &[Vec<u8>]) -> Vec<u8> {
+        let mut bytes = b"ASEF".to_vec();
+        bytes.extend_from_slice(&[0, 1, 0, 0]);
+        bytes.extend_from_slice(&(blocks.len() as u32).to_be_bytes());
+        for block in blocks {
+            bytes.extend_from_slice(block);
+        }
+        bytes
+    }
+
+    #[test]
+    fn reads_ase_swatches() {
+        let group_start = vec![0xc0, 0x01, 0, 0, 0, 6, 0, 2, 0, b'g', 0, 0];
+        let group_end = vec![0xc0, 0x02, 0, 0, 0, 0];
+        let bytes = ase_file(&[
+            group_start,
+            ase_colour(b"RGB ", &[1.0, 0.5, 0.0]),
+            ase_colour(b"Gray", &[0.25]),
+            group_end,
+            ase_colour(b"CMYK", &[1.0, 0.0, 0.0, 0.0]),
+        ]);
+
+        let colours = from_ase(&bytes).unwrap();
+
+        assert_eq!(colours.len(), 3);
+        assert_eq!(colours[0].get(), (1.0, 0.5, 0.0));
+        assert_eq!(colours[1].get(), (0.25, 0.25, 0.25));
+        assert_eq!(colours[2].get(), (0.0, 1.0, 1.0));
+    }
+
+    #[test]
+    fn rejects_invalid_ase() {
+        assert_eq!(from_ase(b"NOPE").unwrap_err(), PaletteError::InvalidHeader);
+
+        let mut truncated = ase_file(&[ase_colour(b"RGB ", &[1.0, 0.5, 0.0])]);
+        truncated.truncate(truncated.len() - 3);
+        assert_eq!(from_ase(&truncated).unwrap_err(), PaletteError::UnexpectedEnd);
+
+        let unknown = ase_file(&[ase_colour(b"RGB ", &[1.0, 0.5, 0.0]), vec![0xbe, 0xef, 0, 0, 0, 0]]);
+        assert_eq!(from_ase(&unknown).unwrap_err(), PaletteError::UnknownBlock(0xbeef));
+        assert_eq!(PaletteError::UnknownBlock(0xbeef).to_string(), "unknown block type: 0xbeef");
+    }
+
+    #[test]
+    fn exact_palette_has_no_quantization_error() {
+        let pixels = [RED, colours::GOLD, colours::AQUAMARINE, RED];
+        let palette = [RED, colours::GOLD, colours::AQUAMARINE];
+        let quantized: Vec<RgbPixel> = pixels.iter().map(|pixel| pixel.quantize(&palette)).collect();
+
+        for metric in [DeltaEMethod::WeightedEuclidean, DeltaEMethod::Cie76, DeltaEMethod::Cie94, DeltaEMethod::Ciede2000, DeltaEMethod::Ok] {
+            assert_eq!(quantization_error(&pixels, &quantized, metric), Ok(0.0));
+            assert_eq!(max_quantization_error(&pixels, &quantized, metric), Ok(0.0));
+        }
+    }
+
+    #[test]
+    fn quantization_error_mean_and_max() {
+        let pixels = [RED, colours::ORANGE];
+        let quantized = [RED, RED];
+
+        let mean = quantization_error(&pixels, &quantized, DeltaEMethod::Ok).unwrap();
+        let max = max_quantization_error(&pixels, &quantized, DeltaEMethod::Ok).unwrap();
+        assert!((max - colours::ORANGE.delta_e(&RED, DeltaEMethod::Ok)).abs() < 1e-6);
+        assert!((mean - max / 2.0).abs() < 1e-6);
+
+        assert_eq!(quantization_error(&pixels, &quantized[..1], DeltaEMethod::Ok), Err(LengthMismatch { expected: 2, found: 1 }));
+    }
+
+    #[test]
+    fn dedup_collapses_near_duplicates() {
+        use crate::pixel::lch::LchPixel;
+
+        let a = LchPixel(50.0, 30.0, 120.0).as_rgb();
+        let b = LchPixel(50.5, 30.0, 120.0).as_rgb();
+        assert!((a.delta_e(&b, DeltaEMethod::Ciede2000) - 0.5).abs() < 0.05);
+
+        let deduped = dedup_palette(&[a, RED, b], 2.0);
+        assert_eq!(deduped.len(), 2);
+        assert_eq!(deduped[0].get(), a.get());
+        assert_eq!(deduped[1].get(), RED.get());
+
+        let distinct = [RED, GREEN, BLUE, colours::GOLD, colours::BLACK, colours::WHITE];
+        assert_eq!(dedup_palette(&distinct, 2.0).len(), distinct.len());
+    }
+
+    #[test]
+    fn masked_stripe_is_quantized_flat() {
+        let (width, height) = (8, 6);
+        let pixels = vec![RgbPixel(0.4, 0.4, 0.4); width * height];
+        let palette = [colours::BLACK, colours::WHITE];
+
+        // the middle two rows are masked out
+        let mask: Vec<bool> = (0..pixels.len()).map(|index| (2..4).contains(&(index / width))).collect();
+        let quantized = quantize_image(&pixels, width, &palette, Some(&mask)).unwrap();
+
+        let is_white = |pixel: &RgbPixel| pixel.get() == colours::WHITE.get();
+        let (masked, unmasked): (Vec<_>, Vec<_>) = quantized.iter().zip(&mask).partition(|(_, masked)| **masked);
+
+        assert!(masked.iter().all(|(pixel, _)| !is_white(pixel)));
+        assert!(unmasked.iter().any(|(pixel, _)| is_white(pixel)));
+        assert!(unmasked.iter().any(|(pixel, _)| !is_white(pixel)));
+
+        // without a mask, the stripe dithers too
+        let unmasked = quantize_image(&pixels, width, &palette, None).unwrap();
+        assert!(unmasked[2 * width..4 * width].iter().any(is_white));
+    }
+
+    #[test]
+    fn quantize_image_rejects_short_mask() {
+        let pixels = [RgbPixel(0.4, 0.4, 0.4); 4];
+        let mask = [false; 3];
+
+        assert_eq!(
+            quantize_image(&pixels, 2, &[colours::BLACK, colours::WHITE], Some(&mask)).unwrap_err(),
+            LengthMismatch { expected: 4, found: 3 },
+        );
+    }
+
+    #[test]
+    fn binary_palette_round_trips() {
+        let palette = [RED, GREEN, BLUE, RgbPixel::from((12, 34, 56))];
+        let bytes = palette_to_bytes(&palette);
+
+        assert_eq!(bytes.len(), 8 + palette.len() * 3);
+        assert_eq!(&bytes[..4], b"CXPL");
+
+        let read: Vec<_> = palette_from_bytes(&bytes).unwrap().iter().map(RgbPixel::get_u8).collect();
+        let expected: Vec<_> = palette.iter().map(RgbPixel::get_u8).collect();
+        assert_eq!(read, expected);
+
+        assert!(palette_from_bytes(&palette_to_bytes(&[])).unwrap().is_empty());
+    }
+
+    #[test]
+    fn binary_palette_rejects_corrupt_buffers() {
+        let bytes = palette_to_bytes(&[RED, GREEN]);
+
+        assert_eq!(palette_from_bytes(&bytes[..bytes.len() - 1]).unwrap_err(), PaletteError::UnexpectedEnd);
+        assert_eq!(palette_from_bytes(&bytes[..6]).unwrap_err(), PaletteError::UnexpectedEnd);
+        assert_eq!(palette_from_bytes(&[bytes.as_slice(), &[0]].concat()).unwrap_err(), PaletteError::TrailingBytes(1));
+
+        let mut corrupt = bytes.clone();
+        corrupt[0] = b'X';
+        assert_eq!(palette_from_bytes(&corrupt).unwrap_err(), PaletteError::InvalidHeader);
+    }
+
+    #[test]
+    fn jasc_palette_round_trips() {
+        let palette = [RED, RgbPixel::from((18u8, 52u8, 86u8)), colours::BLACK];
+        let text = to_jasc_pal(&palette);
+
+        assert_eq!(text, "JASC-PAL\r\n0100\r\n3\r\n255 0 0\r\n18 52 86\r\n0 0 0\r\n");
+
+        let read = from_jasc_pal(&text).unwrap();
+        assert_eq!(read.iter().map(RgbPixel::get_u8).collect::<Vec<_>>(), palette.iter().map(RgbPixel::get_u8).collect::<Vec<_>>());
+
+        assert_eq!(from_jasc_pal("JASC-PAL\n0100\n1\n  0 128   255 \n\n").unwrap()[0].get_u8(), (0, 128, 255));
+        assert!(from_jasc_pal(&to_jasc_pal(&[])).unwrap().is_empty());
+    }
+
+    #[test]
+    fn jasc_palette_rejects_invalid_text() {
+        assert_eq!(from_jasc_pal("JASC-PAL\n0100\n3\n255 0 0\n0 0 0\n").unwrap_err(), PaletteError::CountMismatch { declared: 3, found: 2 });
+        assert_eq!(from_jasc_pal("JASC-PAL\n0100\n1\n255 0 0\n0 0 0\n").unwrap_err(), PaletteError::CountMismatch { declared: 1, found: 2 });
+
+        assert_eq!(from_jasc_pal("GIMP Palette\n").unwrap_err(), PaletteError::InvalidHeader);
+        assert_eq!(from_jasc_pal("JASC-PAL\n0100\nmany\n").unwrap_err(), PaletteError::InvalidHeader);
+        assert_eq!(from_jasc_pal("JASC-PAL\n0100\n1\n256 0 0\n").unwrap_err(), PaletteError::InvalidColour(String::from("256 0 0")));
+        assert_eq!(from_jasc_pal("JASC-PAL\n0100\n1\n255 0\n").unwrap_err(), PaletteError::InvalidColour(String::from("255 0")));
+    }
+
+    #[test]
+    fn weighted_indices_count_usage() {
+        let pixels = [RED, RgbPixel(0.9, 0.1, 0.0), BLUE, RED, RgbPixel(0.0, 0.1, 0.9)];
+        let palette = [RED, GREEN, BLUE];
+
+        let (indices, counts) = quantize_indices_weighted(&pixels, &palette);
+
+        assert_eq!(indices, vec![0, 0, 2, 0, 2]);
+        assert_eq!(counts, vec![3, 0, 2]);
+        assert_eq!(counts.iter().sum::<usize>(), pixels.len());
+
+        assert_eq!(quantize_indices_weighted(&pixels, &[]), (vec![], vec![]));
+    }
+
+    #[test]
+    fn perceptual_quantize_matches_lab() {
+        use crate::pixel::lab::LabPixel;
+
+        let palette = golden_palette(12, 0.6, 0.5, 20.0);
+        let lab_palette: Vec<LabPixel> = palette.iter().map(RgbPixel::as_lab).collect();
+        let prepared = PreparedPalette::new(&palette, DeltaEMethod::Cie76);
+
+        for step in 0..64 {
+            let pixel = RgbPixel((step % 4) as f32 / 3.0, (step / 4 % 4) as f32 / 3.0, (step / 16) as f32 / 3.0);
+            let expected = pixel.as_lab().quantize(&lab_palette).get();
+
+            assert_eq!(pixel.quantize_perceptual(&palette, DeltaEMethod::Cie76).as_lab().get(), expected, "{:?}", pixel);
+            assert_eq!(prepared.quantize(&pixel).as_lab().get(), expected, "{:?}", pixel);
+        }
+    }
+
+    #[test]
+    fn prepared_palette_matches_delta_e_on_grid() {
+        let mut palette = golden_palette(12, 0.6, 0.5, 20.0);
+        palette.extend([colours::BLACK, RgbPixel(0.5, 0.5, 0.5), colours::WHITE]);
+
+        for metric in [DeltaEMethod::WeightedEuclidean, DeltaEMethod::Cie76, DeltaEMethod::Cie94, DeltaEMethod::Ciede2000, DeltaEMethod::Ok] {
+            let prepared = PreparedPalette::new(&palette, metric);
+
+            // the grid includes greys - which CIE94 can't measure the hue difference of
+            for step in 0..64 {
+                let pixel = RgbPixel((step % 4) as f32 / 3.0, (step / 4 % 4) as f32 / 3.0, (step / 16) as f32 / 3.0);
+                let distance = |index: &usize| match pixel.delta_e(&palette[*index], metric) {
+                    distance if distance.is_nan() => f32::INFINITY,
+                    distance => distance,
+                };
+                let nearest = (0..palette.len()).min_by(|a, b| distance(a).total_cmp(&distance(b)));
+
+                assert!(nearest.is_some());
+                assert_eq!(prepared.quantize_index(&pixel), nearest, "{:?} with {:?}", pixel, metric);
+            }
+        }
+    }
+
+    #[test]
+    fn prepared_palette_matches_delta_e() {
+        let palette = [RED, GREEN, BLUE, colours::ORANGE, colours::AQUAMARINE];
+        let pixel = RgbPixel(0.8, 0.5, 0.1);
+
+        for metric in [DeltaEMethod::WeightedEuclidean, DeltaEMethod::Cie76, DeltaEMethod::Cie94, DeltaEMethod::Ciede2000, DeltaEMethod::Ok] {
+            let prepared = PreparedPalette::new(&palette, metric);
+            let nearest = (0..palette.len()).min_by(|a, b| pixel.delta_e(&palette[*a], metric).total_cmp(&pixel.delta_e(&palette[*b], metric)));
+
+            assert_eq!(prepared.quantize_index(&pixel), nearest, "{:?}", metric);
+            assert_eq!(prepared.metric(), metric);
+        }
+
+        assert_eq!(PreparedPalette::new(&[], DeltaEMethod::Ok).quantize(&pixel).get(), pixel.get());
+        assert_eq!(PreparedPalette::new(&palette, DeltaEMethod::Ok).palette().len(), palette.len());
+    }
+}
@@ -0,0 +1,237 @@
+use crate::{
+    colour::Colour,
+    named::named_colour,
+    pixel::{lab::LabPixel, lch::LchPixel, oklab::OklabPixel, oklch::OklchPixel, rgb::RgbPixel, rgba::RgbaPixel},
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// The ways that parsing a colour from a string can fail.
+pub enum ParseColourError {
+    /// The string had the wrong number of digits.
+    InvalidLength(usize),
+    /// The string contained a character that isn't a valid digit.
+    InvalidDigit(char),
+    /// The string isn't a hex colour, a named colour, or a supported colour function.
+    UnknownColour(String),
+    /// A colour function had the wrong number of components.
+    InvalidComponentCount(usize),
+    /// A component of a colour function isn't a number, percentage, or `none`.
+    InvalidComponent(String),
+    /// A number was outside of the range it's allowed to be in.
+    OutOfRange(String),
+}
+
+impl std::fmt::Display for ParseColourError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseColourError::InvalidLength(length) => write!(f, "invalid number of digits: {}", length),
+            ParseColourError::InvalidDigit(digit) => write!(f, "invalid digit: {:?}", digit),
+            ParseColourError::UnknownColour(colour) => write!(f, "unknown colour: {:?}", colour),
+            ParseColourError::InvalidComponentCount(count) => write!(f, "expected 3 components, found {}", count),
+            ParseColourError::InvalidComponent(component) => write!(f, "invalid component: {:?}", component),
+            ParseColourError::OutOfRange(value) => write!(f, "out of range: {}", value),
+        }
+    }
+}
+
+impl std::error::Error for ParseColourError {}
+
+#[derive(Debug, Clone, Copy)]
+/// A parsed hex colour - which remembers whether an alpha was specified.
+///
+/// `#ff0000` and `#ff0000ff` look identical, but only the latter explicitly sets the alpha.
+pub enum ParsedHex {
+    Rgb(RgbPixel),
+    Rgba(RgbaPixel),
+}
+
+impl ParsedHex {
+    /// Retrieves the colour as an `RgbaPixel`, treating a missing alpha as fully opaque.
+    pub fn as_rgba(&self) -> RgbaPixel {
+        match self {
+            ParsedHex::Rgb(rgb) => RgbaPixel::from(*rgb),
+            ParsedHex::Rgba(rgba) => *rgba,
+        }
+    }
+}
+
+/// Parses a hex colour, with or without a leading `#`.
+///
+/// Accepts the short (`rgb`, `rgba`) and long (`rrggbb`, `rrggbbaa`) forms - with the 4 and 8 digit
+/// forms returning `ParsedHex::Rgba`.
+pub fn parse_hex(s: &str) -> Result<ParsedHex, ParseColourError> {
+    let digits = s.trim().strip_prefix('#').unwrap_or(s.trim());
+
+    let values = digits
+        .chars()
+        .map(|digit| digit.to_digit(16).map(|value| value as u8).ok_or(ParseColourError::InvalidDigit(digit)))
+        .collect::<Result<Vec<u8>, _>>()?;
+
+    let channels: Vec<u8> = match values.len() {
+        3 | 4 => values.iter().map(|value| value * 17).collect(),
+        6 | 8 => values.chunks(2).map(|pair| pair[0] * 16 + pair[1]).collect(),
+        length => return Err(ParseColourError::InvalidLength(length)),
+    };
+
+    Ok(match channels[..] {
+        [r, g, b] => ParsedHex::Rgb((r, g, b).into()),
+        [r, g, b, a] => ParsedHex::Rgba((r, g, b, a).into()),
+        _ => unreachable!("channels always has 3 or 4 values"),
+    })
+}
+
+/// Parses a CSS colour - a hex colour, a named colour, or one of the `lab()`, `lch()`, `oklab()` and `oklch()` functions.
+///
+/// Components can be numbers or percentages, and hues can have a `deg`, `grad`, `rad` or `turn` unit. A `none` component is `0`, except
+/// for hues - which become achromatic (`NaN`). Any alpha (after a `/`, or in a hex colour) is ignored.
+pub fn parse_css_colour(s: &str) -> Result<Colour, ParseColourError> {
+    let s = s.trim();
+
+    if s.starts_with('#') {
+        return Ok(Colour::Rgb(parse_hex(s)?.as_rgba().rgb()));
+    }
+
+    let Some((function, arguments)) = s.strip_suffix(')').and_then(|s| s.split_once('(')) else {
+        return named_colour(s).map(Colour::Rgb).ok_or_else(|| ParseColourError::UnknownColour(s.to_string()));
+    };
+
+    // the alpha (if any) comes after a slash
+    let components: Vec<&str> = arguments.split('/').next().unwrap_or("").split_whitespace().collect();
+    let [first, second, third] = components[..] else {
+        return Err(ParseColourError::InvalidComponentCount(components.len()));
+    };
+
+    // percentages are relative to the reference range of each component in the CSS spec
+    Ok(match function.trim().to_ascii_lowercase().as_str() {
+        "lab" => LabPixel(css_component(first, 100.0)?, css_component(second, 125.0)?, css_component(third, 125.0)?).into(),
+        "lch" => LchPixel(css_component(first, 100.0)?, css_component(second, 150.0)?, css_hue(third)?).into(),
+        "oklab" => OklabPixel(css_component(first, 1.0)?, css_component(second, 0.4)?, css_component(third, 0.4)?).into(),
+        "oklch" => OklchPixel(css_component(first, 1.0)?, css_component(second, 0.4)?, css_hue(third)?).into(),
+        _ => return Err(ParseColourError::UnknownColour(s.to_string())),
+    })
+}
+
+/// Parses a component of a colour function - where `100%` is `reference`.
+fn css_component(component: &str, reference: f32) -> Result<f32, ParseColourError> {
+    let invalid = || ParseColourError::InvalidComponent(component.to_string());
+
+    if component.eq_ignore_ascii_case("none") {
+        Ok(0.0)
+    } else if let Some(percentage) = component.strip_suffix('%') {
+        percentage.parse::<f32>().map(|value| value / 100.0 * reference).map_err(|_| invalid())
+    } else {
+        component.parse().map_err(|_| invalid())
+    }
+}
+
+/// Parses a hue component of a colour function into degrees - where `none` is achromatic (`NaN`).
+/// A hue without a unit is in degrees.
+fn css_hue(component: &str) -> Result<f32, ParseColourError> {
+    // (`grad` has to be checked before `rad`)
+    const UNITS: [(&str, f32); 4] = [("deg", 1.0), ("grad", 0.9), ("rad", 180.0 / std::f32::consts::PI), ("turn", 360.0)];
+
+    if component.eq_ignore_ascii_case("none") {
+        return Ok(f32::NAN);
+    }
+
+    let lowercase = component.to_ascii_lowercase();
+    let (number, degrees_per_unit) = UNITS
+        .iter()
+        .find_map(|(unit, scale)| lowercase.strip_suffix(unit).map(|number| (number, *scale)))
+        .unwrap_or((lowercase.as_str(), 1.0));
+
+    number
+        .parse::<f32>()
+        .map(|value| value * degrees_per_unit)
+        .map_err(|_| ParseColourError::InvalidComponent(component.to_string()))
+}
+
+#[cfg(test)]
+mod test {
+    use super::{parse_css_colour, parse_hex, ParseColourError, ParsedHex};
+    use crate::{colour::Colour, pixel::{assert_close, lab::LabPixel, lch::LchPixel, oklch::OklchPixel}};
+
+    #[test]
+    fn css_round_trips() {
+        let oklch = OklchPixel(0.7, 0.15, 30.0);
+        assert_eq!(oklch.to_css(), "oklch(70% 0.15 30)");
+        match parse_css_colour(&oklch.to_css()) {
+            Ok(Colour::Oklch(parsed)) => assert_close(parsed.get(), oklch.get(), 0.01),
+            other => panic!("Expected an OKLCH colour, got {:?}", other),
+        }
+
+        let lab = LabPixel(54.29, 80.8, 69.89);
+        match parse_css_colour(&lab.to_css()) {
+            Ok(Colour::Lab(parsed)) => assert_close(parsed.get(), lab.get(), 0.01),
+            other => panic!("Expected a LAB colour, got {:?}", other),
+        }
+
+        let lch = LchPixel(54.29, 106.84, 40.85);
+        match parse_css_colour(&lch.to_css()) {
+            Ok(Colour::Lch(parsed)) => assert_close(parsed.get(), lch.get(), 0.01),
+            other => panic!("Expected an LCH colour, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn css_achromatic_hue() {
+        let grey = OklchPixel(0.5, 0.0, f32::NAN);
+        assert_eq!(grey.to_css(), "oklch(50% 0 none)");
+
+        match parse_css_colour(&grey.to_css()) {
+            Ok(Colour::Oklch(parsed)) => assert_close(parsed.get(), grey.get(), 0.01),
+            other => panic!("Expected an OKLCH colour, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn css_other_forms() {
+        assert!(matches!(parse_css_colour("#ff0000"), Ok(Colour::Rgb(_))));
+        assert!(matches!(parse_css_colour("rebeccapurple"), Ok(Colour::Rgb(_))));
+        assert!(matches!(parse_css_colour("oklch(0.7 40% 30deg / 0.5)"), Ok(Colour::Oklch(OklchPixel(_, c, _))) if (c - 0.16).abs() < 1e-6));
+        assert_eq!(parse_css_colour("lab(50% 20)").unwrap_err(), ParseColourError::InvalidComponentCount(2));
+        assert_eq!(parse_css_colour("lab(50% x 20)").unwrap_err(), ParseColourError::InvalidComponent(String::from("x")));
+        assert!(matches!(parse_css_colour("notacolour"), Err(ParseColourError::UnknownColour(_))));
+    }
+
+    #[test]
+    fn css_hue_units() {
+        let hue = |css: &str| match parse_css_colour(css) {
+            Ok(Colour::Oklch(parsed)) => parsed.2,
+            other => panic!("Expected an OKLCH colour, got {:?}", other),
+        };
+
+        for css in ["oklch(0.7 0.1 90)", "oklch(0.7 0.1 90deg)", "oklch(0.7 0.1 100grad)", "oklch(0.7 0.1 0.25turn)", "oklch(0.7 0.1 1.5708rad)"] {
+            assert!((hue(css) - 90.0).abs() < 1e-3, "{} was {}", css, hue(css));
+        }
+        assert!((hue("oklch(0.7 0.1 0.5TURN)") - 180.0).abs() < 1e-3);
+
+        assert_eq!(parse_css_colour("lch(50 20 30rads)").unwrap_err(), ParseColourError::InvalidComponent(String::from("30rads")));
+    }
+
+    #[test]
+    fn long_forms() {
+        assert!(matches!(parse_hex("#ff0000"), Ok(ParsedHex::Rgb(_))));
+
+        match parse_hex("ff000080") {
+            Ok(ParsedHex::Rgba(rgba)) => assert_eq!(rgba.get(), (1.0, 0.0, 0.0, 128.0 / 255.0)),
+            other => panic!("Expected an RGBA colour, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn short_forms() {
+        match parse_hex("#f80") {
+            Ok(ParsedHex::Rgb(rgb)) => assert_eq!(rgb.get_u8(), (255, 136, 0)),
+            other => panic!("Expected an RGB colour, got {:?}", other),
+        }
+
+        assert!(matches!(parse_hex("#f80f"), Ok(ParsedHex::Rgba(_))));
+    }
+
+    #[test]
+    fn invalid_input() {
+        assert_eq!(parse_hex("#ff000").unwrap_err(), ParseColourError::InvalidLength(5));
+        assert_eq!(parse_hex("#gg0000").unwrap_err(), ParseColourError::InvalidDigit('g'));
+    }
+}
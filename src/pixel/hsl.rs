@@ -1,7 +1,9 @@
-use super::{rgb::RgbPixel};
-use crate::conversions::{rgb_to_hsl, hsl_to_rgb};
+use std::ops::RangeInclusive;
 
-#[derive(Debug, Clone, Copy)]
+use super::{rgb::RgbPixel, Cylindrical, round_decimals};
+use crate::conversions::{rgb_to_hsl, hsl_to_rgb, lerp_hue, snap_hue};
+
+#[derive(Debug, Clone, Copy, Default)]
 /// Represents a pixel in the HSL colour space. Saturation and luminance are clamped at `0.0` to `1.0` - whereas hue can be any valid `f32` value.
 /// The 3 components of an HSL pixel are as follows:
 /// 
@@ -13,12 +15,29 @@ use crate::conversions::{rgb_to_hsl, hsl_to_rgb};
 /// components more accurately reflect human vision.
 pub struct HslPixel(pub f32, pub f32, pub f32);
 
+impl Cylindrical for HslPixel {
+    fn hue(&self) -> f32 {
+        self.0
+    }
+
+    fn set_hue(&mut self, hue: f32) {
+        self.0 = hue;
+    }
+}
+
 impl From<(f32, f32, f32)> for HslPixel {
     fn from(value: (f32, f32, f32)) -> Self {
         HslPixel(value.0, value.1, value.2)
     }
 }
 
+/// Each component is rounded to the nearest `f32`.
+impl From<(f64, f64, f64)> for HslPixel {
+    fn from(value: (f64, f64, f64)) -> Self {
+        HslPixel(value.0 as f32, value.1 as f32, value.2 as f32)
+    }
+}
+
 impl From<RgbPixel> for HslPixel {
     fn from(value: RgbPixel) -> Self {
         Self::from_rgb(&value)
@@ -32,55 +51,73 @@ impl Into<RgbPixel> for HslPixel {
 }
 
 impl HslPixel {
+    /// The range of the hue. Hue wraps around, so any value is technically valid.
+    pub const H_RANGE: RangeInclusive<f32> = 0.0..=360.0;
+    /// The valid range of the saturation.
+    pub const S_RANGE: RangeInclusive<f32> = 0.0..=1.0;
+    /// The valid range of the luminance.
+    pub const L_RANGE: RangeInclusive<f32> = 0.0..=1.0;
+
     /// Adds (rotates) the hue.
     pub fn add_hue(&mut self, hue: f32) -> &mut Self {
         self.0 = self.0 + hue;
         self
     }
 
+    /// Adds (rotates) the hue, returning a new pixel.
+    #[must_use]
+    pub fn adjusted_hue(&self, hue: f32) -> Self {
+        let mut pixel = *self;
+        pixel.add_hue(hue);
+        pixel
+    }
+
     /// Adds saturation. Any value can be passed, but the value on the pixel is clamped to `0.0` to `1.0`.
     pub fn add_saturation(&mut self, saturation: f32) -> &mut Self {
-        self.1 = (self.1 + saturation).clamp(0.0, 1.0);
+        self.1 = (self.1 + saturation).clamp(*Self::S_RANGE.start(), *Self::S_RANGE.end());
         self
     }
 
+    /// Adds saturation, returning a new pixel. Clamped the same way as `add_saturation`.
+    #[must_use]
+    pub fn adjusted_saturation(&self, saturation: f32) -> Self {
+        let mut pixel = *self;
+        pixel.add_saturation(saturation);
+        pixel
+    }
+
     /// Adds luminance. Any value can be passed, but the value on the pixel is clamped to `0.0` to `1.0`.
+    ///
+    /// **Note:** This will crush HDR values - use `add_luminance_unclamped` to keep them.
     pub fn add_luminance(&mut self, luminance: f32) -> &mut Self {
-        self.2 = (self.2 + luminance).clamp(0.0, 1.0);
+        self.2 = (self.2 + luminance).clamp(*Self::L_RANGE.start(), *Self::L_RANGE.end());
         self
     }
 
-    pub fn quantize_hue(&mut self, hues: &[f32]) -> &mut Self {
-        let mut closest_dist = f32::MAX;
-        let pixel_hue = self.get_normalized_hue();
-        let mut current_hue = pixel_hue;
-
-        for hue in hues.iter() {
-            let normalized = Self::normalize_hue(*hue);
-            let distance = (normalized - pixel_hue).abs();
-            if distance < closest_dist {
-                closest_dist = distance;
-                current_hue = normalized;
-            }
-        }
-
-        self.0 = current_hue;
+    /// Adds luminance, returning a new pixel. Clamped the same way as `add_luminance`.
+    #[must_use]
+    pub fn adjusted_luminance(&self, luminance: f32) -> Self {
+        let mut pixel = *self;
+        pixel.add_luminance(luminance);
+        pixel
+    }
+
+    /// Adds luminance without clamping, so HDR values survive.
+    pub fn add_luminance_unclamped(&mut self, luminance: f32) -> &mut Self {
+        self.2 += luminance;
         self
     }
 
-    /// Retrieves the hue as a value between 0 and 360.
-    fn get_normalized_hue(&self) -> f32 {
-        Self::normalize_hue(self.0)
+    /// Snaps the hue to the nearest of `n` evenly-spaced hues, starting at `offset` - without building the list
+    /// for `quantize_hue`. The distance is measured around the colour wheel. An `n` of `0` leaves the hue as-is.
+    pub fn quantize_hue_n(&mut self, n: u16, offset: f32) -> &mut Self {
+        self.0 = snap_hue(self.0, n, offset);
+        self
     }
 
-    fn normalize_hue(hue: f32) -> f32 {
-        loop {
-            if hue >= 0.0 {
-                break hue % 360.0;
-            } else {
-                break (hue % 360.0) + 360.0;
-            }
-        }
+    /// Snaps the hue to the nearest of `hues`, measured around the colour wheel. See `Cylindrical::quantize_hue`.
+    pub fn quantize_hue(&mut self, hues: &[f32]) -> &mut Self {
+        Cylindrical::quantize_hue(self, hues)
     }
 
     /// Retrieves the (h, s, l) values.
@@ -88,11 +125,40 @@ impl HslPixel {
         (self.0, self.1, self.2)
     }
 
+    /// Rounds each component to `decimals` decimal places. See `RgbPixel::round_to`.
+    #[must_use]
+    pub fn round_to(&self, decimals: u32) -> Self {
+        HslPixel(round_decimals(self.0, decimals), round_decimals(self.1, decimals), round_decimals(self.2, decimals))
+    }
+
+    /// Linearly interpolates between `a` and `b`, with the hue taking the shortest way around. See `RgbPixel::lerp`.
+    pub fn lerp(a: &HslPixel, b: &HslPixel, t: f32) -> HslPixel {
+        let lerp = |start: f32, end: f32| start + (end - start) * t;
+        HslPixel(lerp_hue(a.0, b.0, t), lerp(a.1, b.1), lerp(a.2, b.2))
+    }
+
     pub fn from_rgb(rgb: &RgbPixel) -> HslPixel {
         rgb_to_hsl(rgb.get()).into()
     }
 
+    #[must_use]
     pub fn as_rgb(&self) -> RgbPixel {
         hsl_to_rgb(self.get()).into()
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::HslPixel;
+
+    #[test]
+    fn add_saturates_at_ranges() {
+        let mut pixel = HslPixel(0.0, 0.5, 0.5);
+
+        pixel.add_saturation(10.0).add_luminance(10.0);
+        assert_eq!((pixel.1, pixel.2), (*HslPixel::S_RANGE.end(), *HslPixel::L_RANGE.end()));
+
+        pixel.add_saturation(-10.0).add_luminance(-10.0);
+        assert_eq!((pixel.1, pixel.2), (*HslPixel::S_RANGE.start(), *HslPixel::L_RANGE.start()));
+    }
+}
@@ -1,7 +1,9 @@
-use super::{rgb::RgbPixel, lch::LchPixel};
-use crate::{conversions::{chain_conversions, rgb_to_xyz_d65, xyz_d65_to_xyz_d50, xyz_d50_to_lab, lab_to_xyz_d50, xyz_d50_to_xyz_d65, xyz_d65_to_rgb}, comparisons::cie76};
+use std::ops::RangeInclusive;
 
-#[derive(Debug, Clone, Copy)]
+use super::{linear_rgb::LinearRgbPixel, rgb::{GamutStatus, OutOfGamut, RgbPixel}, lch::LchPixel, css_number, round_decimals};
+use crate::{conversions::{chain_conversions, linear_rgb_to_srgb, srgb_to_linear_rgb, rgb_to_xyz_d65, xyz_d65_to_xyz_d50, xyz_d50_to_lab, lab_to_xyz_d50, xyz_d50_to_xyz_d65, xyz_d65_to_rgb, xyz_d65_to_lab_d65, lab_d65_to_xyz_d65}, comparisons::cie76};
+
+#[derive(Debug, Clone, Copy, Default)]
 /// The 3 components of an LAB pixel are:
 /// 
 /// - L: Ranges from 0.0 to 100.0. Determines the visible luminance of the pixel.
@@ -10,6 +12,9 @@ use crate::{conversions::{chain_conversions, rgb_to_xyz_d65, xyz_d65_to_xyz_d50,
 /// 
 /// The nature of this pixel can be a bit finnicky to play with. You may prefer to use
 /// LCH - which replaces `a` and `b` with `Chroma` (saturation) and `Hue`.
+///
+/// LAB is relative to a white point - and unless stated otherwise (see `from_rgb_d65`), this crate
+/// uses D50, adapting from the D65 of RGB first.
 pub struct LabPixel(pub f32, pub f32, pub f32);
 
 impl From<(f32, f32, f32)> for LabPixel {
@@ -19,6 +24,40 @@ impl From<(f32, f32, f32)> for LabPixel {
     }
 }
 
+/// Each component is rounded to the nearest `f32`.
+impl From<(f64, f64, f64)> for LabPixel {
+    fn from(value: (f64, f64, f64)) -> Self {
+        LabPixel(value.0 as f32, value.1 as f32, value.2 as f32)
+    }
+}
+
+/// Adds the components together - treating the pixel as a vector, like for shifting colours by an offset.
+impl std::ops::Add for LabPixel {
+    type Output = LabPixel;
+
+    fn add(self, rhs: LabPixel) -> Self::Output {
+        LabPixel(self.0 + rhs.0, self.1 + rhs.1, self.2 + rhs.2)
+    }
+}
+
+/// Subtracts the components - the offset from `rhs` to `self`.
+impl std::ops::Sub for LabPixel {
+    type Output = LabPixel;
+
+    fn sub(self, rhs: LabPixel) -> Self::Output {
+        LabPixel(self.0 - rhs.0, self.1 - rhs.1, self.2 - rhs.2)
+    }
+}
+
+/// Scales every component (lightness included) by `rhs`.
+impl std::ops::Mul<f32> for LabPixel {
+    type Output = LabPixel;
+
+    fn mul(self, rhs: f32) -> Self::Output {
+        LabPixel(self.0 * rhs, self.1 * rhs, self.2 * rhs)
+    }
+}
+
 impl From<RgbPixel> for LabPixel {
     fn from(value: RgbPixel) -> Self {
         Self::from_rgb(&value)
@@ -32,10 +71,51 @@ impl Into<RgbPixel> for LabPixel {
 }
 
 impl LabPixel {
+    /// The valid range of the luma.
+    pub const L_RANGE: RangeInclusive<f32> = 0.0..=100.0;
+    /// The (rough) range of the green-red axis.
+    pub const A_RANGE: RangeInclusive<f32> = -125.0..=125.0;
+    /// The (rough) range of the blue-yellow axis.
+    pub const B_RANGE: RangeInclusive<f32> = -125.0..=125.0;
+
     pub fn get(&self) -> (f32, f32, f32) {
         (self.0, self.1, self.2)
     }
 
+    /// Rounds each component to `decimals` decimal places. See `RgbPixel::round_to`.
+    #[must_use]
+    pub fn round_to(&self, decimals: u32) -> Self {
+        LabPixel(round_decimals(self.0, decimals), round_decimals(self.1, decimals), round_decimals(self.2, decimals))
+    }
+
+    /// Linearly interpolates between `a` and `b`. See `RgbPixel::lerp`.
+    pub fn lerp(a: &LabPixel, b: &LabPixel, t: f32) -> LabPixel {
+        let lerp = |start: f32, end: f32| start + (end - start) * t;
+        LabPixel(lerp(a.0, b.0), lerp(a.1, b.1), lerp(a.2, b.2))
+    }
+
+    /// Retrieves the angle of the (a, b) chroma vector in degrees, from `0.0` to `360.0` - where `0.0` is +a (red),
+    /// and `90.0` is +b (yellow).
+    ///
+    /// This is the same as the LCH hue - except that greys aren't treated specially, so a zero vector has an angle of `0.0`
+    /// rather than `NaN`.
+    pub fn chroma_angle(&self) -> f32 {
+        self.2.atan2(self.1).to_degrees().rem_euclid(360.0)
+    }
+
+    /// Retrieves the length of the (a, b) chroma vector - the same as the LCH chroma.
+    pub fn chroma_magnitude(&self) -> f32 {
+        self.1.hypot(self.2)
+    }
+
+    /// Rotates the (a, b) chroma vector by `degrees` (anticlockwise, from +a towards +b) - a hue rotation, keeping the
+    /// lightness and chroma as-is.
+    #[must_use]
+    pub fn rotate_ab(&self, degrees: f32) -> LabPixel {
+        let (sin, cos) = degrees.to_radians().sin_cos();
+        LabPixel(self.0, self.1 * cos - self.2 * sin, self.1 * sin + self.2 * cos)
+    }
+
     pub fn distance_from(&self, other: &LabPixel) -> f32 {
         cie76(self.get(), other.get())
     }
@@ -55,23 +135,182 @@ impl LabPixel {
         current_colour.get().into()
     }
 
+    /// Clamps each component to its range (see `L_RANGE`, `A_RANGE`, and `B_RANGE`), like `RgbPixel::clamp`.
+    #[must_use]
+    pub fn clamp(&self) -> LabPixel {
+        LabPixel(
+            self.0.clamp(*Self::L_RANGE.start(), *Self::L_RANGE.end()),
+            self.1.clamp(*Self::A_RANGE.start(), *Self::A_RANGE.end()),
+            self.2.clamp(*Self::B_RANGE.start(), *Self::B_RANGE.end()),
+        )
+    }
+
+    /// Checks whether each component is within its range.
+    pub fn is_valid(&self) -> bool {
+        Self::L_RANGE.contains(&self.0) && Self::A_RANGE.contains(&self.1) && Self::B_RANGE.contains(&self.2)
+    }
+
     pub fn from_rgb(rgb: &RgbPixel) -> LabPixel {
         chain_conversions(rgb.get(), &[
+            srgb_to_linear_rgb,
             rgb_to_xyz_d65,
             xyz_d65_to_xyz_d50,
             xyz_d50_to_lab,
         ]).into()
     }
 
+    /// Converts linear light straight to LAB - without going through an encoded `RgbPixel` first.
+    ///
+    /// This is the same conversion as `as_lab` after decoding - `from_linear_rgb(&LinearRgbPixel::from_rgb(&rgb))`
+    /// equals `rgb.as_lab()`.
+    pub fn from_linear_rgb(linear: &LinearRgbPixel) -> LabPixel {
+        chain_conversions(linear.get(), &[
+            rgb_to_xyz_d65,
+            xyz_d65_to_xyz_d50,
+            xyz_d50_to_lab,
+        ]).into()
+    }
+
+    /// Formats the pixel as a CSS Color 4 `lab()` colour - such as `lab(54.29% 80.8 69.89)`.
+    #[must_use]
+    pub fn to_css(&self) -> String {
+        format!("lab({}% {} {})", css_number(self.0, 2), css_number(self.1, 2), css_number(self.2, 2))
+    }
+
+    #[must_use]
     pub fn as_rgb(&self) -> RgbPixel {
         chain_conversions(self.get(), &[
             lab_to_xyz_d50,
             xyz_d50_to_xyz_d65,
             xyz_d65_to_rgb,
+            linear_rgb_to_srgb,
         ]).into()
     }
 
+    /// Converts an `RgbPixel` to LAB relative to a D65 white, rather than the default D50.
+    ///
+    /// The result should only be converted back with `as_rgb_d65` - or compared with other D65 LAB colours.
+    pub fn from_rgb_d65(rgb: &RgbPixel) -> LabPixel {
+        chain_conversions(rgb.get(), &[
+            srgb_to_linear_rgb,
+            rgb_to_xyz_d65,
+            xyz_d65_to_lab_d65,
+        ]).into()
+    }
+
+    /// Converts a pixel made by `from_rgb_d65` (LAB relative to D65) back to an `RgbPixel`.
+    #[must_use]
+    pub fn as_rgb_d65(&self) -> RgbPixel {
+        chain_conversions(self.get(), &[
+            lab_d65_to_xyz_d65,
+            xyz_d65_to_rgb,
+            linear_rgb_to_srgb,
+        ]).into()
+    }
+
+    /// Converts the pixel to an `RgbPixel`, returning an error if it's outside of the RGB gamut.
+    ///
+    /// The error still contains the raw RGB colour, so you can decide whether to clamp it, gamut-map it,
+    /// or reject it.
+    pub fn as_rgb_checked(&self) -> Result<RgbPixel, OutOfGamut> {
+        let rgb = self.as_rgb();
+        match rgb.gamut_status() {
+            GamutStatus::InGamut => Ok(rgb),
+            GamutStatus::Clipped { amount, .. } => Err(OutOfGamut { rgb, max_excess: amount }),
+        }
+    }
+
+    /// Converts the pixel to a clamped `RgbPixel`, alongside whether clamping was needed (and by how much).
+    pub fn to_rgb_report(&self) -> (RgbPixel, GamutStatus) {
+        let rgb = self.as_rgb();
+        (rgb.clamp(), rgb.gamut_status())
+    }
+
+    /// Runs `to_rgb_report` over an entire palette.
+    pub fn to_rgb_reports(palette: &[LabPixel]) -> Vec<(RgbPixel, GamutStatus)> {
+        palette.iter().map(|pixel| pixel.to_rgb_report()).collect()
+    }
+
+    #[must_use]
     pub fn as_lch(&self) -> LchPixel {
         LchPixel::from_lab(self)
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod test {
+    use super::LabPixel;
+
+    #[test]
+    fn in_gamut_converts() {
+        let rgb = LabPixel(50.0, 10.0, -10.0).as_rgb_checked();
+
+        assert!(rgb.is_ok());
+    }
+
+    #[test]
+    fn out_of_gamut_errors() {
+        let lab = LabPixel(50.0, -120.0, 120.0);
+        let error = lab.as_rgb_checked().unwrap_err();
+
+        let raw = lab.as_rgb();
+        assert_eq!(error.rgb.get(), raw.get());
+        assert!(error.max_excess > 0.0);
+    }
+
+    #[test]
+    fn d50_and_d65_differ_but_round_trip() {
+        use crate::pixel::rgb::RgbPixel;
+
+        let rgb = RgbPixel(0.8, 0.4, 0.2);
+        let (d50, d65) = (LabPixel::from_rgb(&rgb), LabPixel::from_rgb_d65(&rgb));
+
+        assert!((d50.1 - d65.1).abs() > 0.5 || (d50.2 - d65.2).abs() > 0.5, "{:?} vs {:?}", d50, d65);
+
+        let close = |a: RgbPixel| (a.0 - rgb.0).abs() < 1e-3 && (a.1 - rgb.1).abs() < 1e-3 && (a.2 - rgb.2).abs() < 1e-3;
+        assert!(close(d50.as_rgb()));
+        assert!(close(d65.as_rgb_d65()));
+
+        // white is neutral relative to its own white point
+        let white = LabPixel::from_rgb_d65(&RgbPixel(1.0, 1.0, 1.0));
+        assert!((white.0 - 100.0).abs() < 0.1 && white.1.abs() < 0.1 && white.2.abs() < 0.1, "{:?}", white);
+    }
+
+    #[test]
+    fn clamp_brings_components_into_range() {
+        let pixel = LabPixel(120.0, -150.0, 30.0);
+        assert!(!pixel.is_valid());
+
+        let clamped = pixel.clamp();
+        assert!(clamped.is_valid());
+        assert_eq!(clamped.get(), (100.0, -125.0, 30.0));
+
+        let valid = LabPixel(50.0, 20.0, -20.0);
+        assert!(valid.is_valid());
+        assert_eq!(valid.clamp().get(), valid.get());
+    }
+
+    #[test]
+    fn rotate_ab_turns_a_into_b() {
+        let rotated = LabPixel(50.0, 40.0, 0.0).rotate_ab(90.0);
+
+        assert_eq!(rotated.0, 50.0);
+        assert!(rotated.1.abs() < 1e-4 && (rotated.2 - 40.0).abs() < 1e-4, "{:?}", rotated);
+        assert!((rotated.chroma_angle() - 90.0).abs() < 1e-4);
+        assert_eq!(LabPixel(50.0, 0.0, 0.0).chroma_angle(), 0.0);
+    }
+
+    #[test]
+    fn chroma_matches_lch() {
+        for lab in [LabPixel(50.0, 40.0, -30.0), LabPixel(80.0, -20.0, 60.0), LabPixel(30.0, -5.0, -45.0)] {
+            let lch = lab.as_lch();
+
+            assert!((lab.chroma_magnitude() - lch.1).abs() < 1e-4);
+            assert!((lab.chroma_angle() - lch.2).abs() < 1e-3);
+            assert!((lab.rotate_ab(135.0).chroma_magnitude() - lch.1).abs() < 1e-4);
+        }
+
+        let offset = LabPixel(80.0, -20.0, 60.0) - LabPixel(50.0, 40.0, -30.0);
+        assert_eq!((LabPixel(50.0, 40.0, -30.0) + offset * 0.5).get(), (65.0, 10.0, 15.0));
+    }
+}
@@ -1,7 +1,9 @@
-use super::{lab::LabPixel, rgb::RgbPixel};
-use crate::{conversions::{lab_to_lch, lch_to_lab}, comparisons::{ciede2000, cie94}};
+use std::ops::RangeInclusive;
 
-#[derive(Debug, Clone, Copy)]
+use super::{lab::LabPixel, rgb::{GamutStatus, RgbPixel}, ClampedError, Cylindrical, css_hue, css_number, round_decimals};
+use crate::{conversions::{lab_to_lch, lch_to_lab, lerp_hue, snap_hue}, comparisons::{ciede2000, cie94}};
+
+#[derive(Debug, Clone, Copy, Default)]
 /// The 3 components of an LCH pixel are as follows:
 /// 
 /// - Lightness: Ranges from 0.0 to 100.0. Determines the visible luminance of the pixel.
@@ -17,6 +19,16 @@ pub mod colours {
     pub static WHITE: LchPixel = LchPixel(100.0, 0.0, 0.0);
 }
 
+impl Cylindrical for LchPixel {
+    fn hue(&self) -> f32 {
+        self.2
+    }
+
+    fn set_hue(&mut self, hue: f32) {
+        self.2 = hue;
+    }
+}
+
 impl From<(f32, f32, f32)> for LchPixel {
     fn from(value: (f32, f32, f32)) -> Self {
         let (l, c, h) = value;
@@ -24,6 +36,13 @@ impl From<(f32, f32, f32)> for LchPixel {
     }
 }
 
+/// Each component is rounded to the nearest `f32`.
+impl From<(f64, f64, f64)> for LchPixel {
+    fn from(value: (f64, f64, f64)) -> Self {
+        LchPixel(value.0 as f32, value.1 as f32, value.2 as f32)
+    }
+}
+
 impl From<RgbPixel> for LchPixel {
     fn from(value: RgbPixel) -> Self {
         Self::from_lab(&LabPixel::from_rgb(&value))
@@ -49,43 +68,106 @@ impl Into<LabPixel> for LchPixel {
 }
 
 impl LchPixel {
+    /// The valid range of the luma.
+    pub const L_RANGE: RangeInclusive<f32> = 0.0..=100.0;
+    /// The (rough) range of the chroma.
+    pub const C_RANGE: RangeInclusive<f32> = 0.0..=150.0;
+    /// The range of the hue. Hue wraps around, so any value is technically valid.
+    pub const H_RANGE: RangeInclusive<f32> = 0.0..=360.0;
+
     pub fn get(&self) -> (f32, f32, f32) {
         (self.0, self.1, self.2)
     }
 
+    /// Rounds each component to `decimals` decimal places. See `RgbPixel::round_to`.
+    #[must_use]
+    pub fn round_to(&self, decimals: u32) -> Self {
+        LchPixel(round_decimals(self.0, decimals), round_decimals(self.1, decimals), round_decimals(self.2, decimals))
+    }
+
+    /// Linearly interpolates between `a` and `b`, with the hue taking the shortest way around. See `RgbPixel::lerp`.
+    pub fn lerp(a: &LchPixel, b: &LchPixel, t: f32) -> LchPixel {
+        let lerp = |start: f32, end: f32| start + (end - start) * t;
+        LchPixel(lerp(a.0, b.0), lerp(a.1, b.1), lerp_hue(a.2, b.2, t))
+    }
+
     pub fn add_luma(&mut self, luma: f32) -> &mut Self {
-        self.0 = (self.0 + luma).clamp(0.0, 100.0);
+        self.0 = (self.0 + luma).clamp(*Self::L_RANGE.start(), *Self::L_RANGE.end());
         self
     }
 
+    /// Adds luma, returning a new pixel. Clamped the same way as `add_luma`.
+    #[must_use]
+    pub fn adjusted_luma(&self, luma: f32) -> Self {
+        let mut pixel = *self;
+        pixel.add_luma(luma);
+        pixel
+    }
+
+    /// Adds luma like `add_luma` - except if it would have to be clamped, an error is returned
+    /// (and the pixel is left untouched) instead.
+    pub fn try_add_luma(&mut self, luma: f32) -> Result<&mut Self, ClampedError> {
+        let requested = self.0 + luma;
+        if !Self::L_RANGE.contains(&requested) {
+            let clamped = requested.clamp(*Self::L_RANGE.start(), *Self::L_RANGE.end());
+            return Err(ClampedError { requested, clamped });
+        }
+
+        self.0 = requested;
+        Ok(self)
+    }
+
     pub fn add_chroma(&mut self, chroma: f32) -> &mut Self {
-        self.1 = (self.1 + chroma).clamp(0.0, 132.0);
+        self.1 = (self.1 + chroma).clamp(*Self::C_RANGE.start(), *Self::C_RANGE.end());
         self
     }
 
+    /// Adds chroma, returning a new pixel. Clamped the same way as `add_chroma`.
+    #[must_use]
+    pub fn adjusted_chroma(&self, chroma: f32) -> Self {
+        let mut pixel = *self;
+        pixel.add_chroma(chroma);
+        pixel
+    }
+
+    /// Adds chroma like `add_chroma` - except if it would have to be clamped, an error is returned
+    /// (and the pixel is left untouched) instead. Useful for catching runaway chroma in generated palettes.
+    pub fn try_add_chroma(&mut self, chroma: f32) -> Result<&mut Self, ClampedError> {
+        let requested = self.1 + chroma;
+        if !Self::C_RANGE.contains(&requested) {
+            let clamped = requested.clamp(*Self::C_RANGE.start(), *Self::C_RANGE.end());
+            return Err(ClampedError { requested, clamped });
+        }
+
+        self.1 = requested;
+        Ok(self)
+    }
+
     pub fn add_hue(&mut self, hue: f32) -> &mut Self {
         self.2 = self.2 + hue;
         self
     }
 
-    pub fn quantize_hue(&mut self, hues: &[f32]) -> &mut Self {
-        let mut closest_dist = f32::MAX;
-        let pixel_hue = ((self.2 % 360.0) + 360.0) % 360.0;
-        let mut current_hue = pixel_hue;
-
-        for hue in hues.iter() {
-            let normalized = ((hue % 360.0) + 360.0) % 360.0;
-            let distance = (normalized - pixel_hue).abs();
-            if distance < closest_dist {
-                closest_dist = distance;
-                current_hue = normalized;
-            }
-        }
+    /// Adds (rotates) the hue, returning a new pixel.
+    #[must_use]
+    pub fn adjusted_hue(&self, hue: f32) -> Self {
+        let mut pixel = *self;
+        pixel.add_hue(hue);
+        pixel
+    }
 
-        self.2 = current_hue;
+    /// Snaps the hue to the nearest of `n` evenly-spaced hues, starting at `offset` - without building the list
+    /// for `quantize_hue`. The distance is measured around the colour wheel. An `n` of `0` leaves the hue as-is.
+    pub fn quantize_hue_n(&mut self, n: u16, offset: f32) -> &mut Self {
+        self.2 = snap_hue(self.2, n, offset);
         self
     }
 
+    /// Snaps the hue to the nearest of `hues`, measured around the colour wheel. See `Cylindrical::quantize_hue`.
+    pub fn quantize_hue(&mut self, hues: &[f32]) -> &mut Self {
+        Cylindrical::quantize_hue(self, hues)
+    }
+
     /// Utilizes CIE94 to allow calculating colour differences with LCH
     pub fn distance_from(&self, other: &LchPixel) -> f32 {
         cie94(self.get(), other.get())
@@ -114,11 +196,109 @@ impl LchPixel {
         rgb.as_lab().as_lch()
     }
 
+    #[must_use]
     pub fn as_lab(&self) -> LabPixel {
         lch_to_lab(self.get()).into()
     }
 
+    /// Formats the pixel as a CSS Color 4 `lch()` colour - such as `lch(54.29% 106.84 40.85)`.
+    ///
+    /// The lightness is a percentage, and an achromatic (`NaN`) hue is written as `none`.
+    #[must_use]
+    pub fn to_css(&self) -> String {
+        format!("lch({}% {} {})", css_number(self.0, 2), css_number(self.1, 2), css_hue(self.2))
+    }
+
+    #[must_use]
     pub fn as_rgb(&self) -> RgbPixel {
         self.as_lab().as_rgb()
     }
-}
\ No newline at end of file
+
+    /// Converts the pixel to a clamped `RgbPixel`, alongside whether clamping was needed (and by how much).
+    pub fn to_rgb_report(&self) -> (RgbPixel, GamutStatus) {
+        let rgb = self.as_rgb();
+        (rgb.clamp(), rgb.gamut_status())
+    }
+
+    /// Runs `to_rgb_report` over an entire palette.
+    pub fn to_rgb_reports(palette: &[LchPixel]) -> Vec<(RgbPixel, GamutStatus)> {
+        palette.iter().map(|pixel| pixel.to_rgb_report()).collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::pixel::{rgb::{Channel, Gamut, GamutStatus}, ClampedError};
+
+    use super::LchPixel;
+
+    #[test]
+    fn add_saturates_at_ranges() {
+        let mut pixel = LchPixel(50.0, 50.0, 0.0);
+
+        pixel.add_luma(1000.0).add_chroma(1000.0);
+        assert_eq!((pixel.0, pixel.1), (*LchPixel::L_RANGE.end(), *LchPixel::C_RANGE.end()));
+
+        pixel.add_luma(-1000.0).add_chroma(-1000.0);
+        assert_eq!((pixel.0, pixel.1), (*LchPixel::L_RANGE.start(), *LchPixel::C_RANGE.start()));
+    }
+
+    #[test]
+    fn adjusted_leaves_original_untouched() {
+        let pixel = LchPixel(50.0, 50.0, 0.0);
+        let adjusted = pixel.adjusted_luma(10.0).adjusted_chroma(1000.0).adjusted_hue(90.0);
+
+        assert_eq!(pixel.get(), (50.0, 50.0, 0.0));
+        assert_eq!(adjusted.get(), (60.0, *LchPixel::C_RANGE.end(), 90.0));
+    }
+
+    #[test]
+    fn reports_clipped_channel() {
+        let pixel = LchPixel(50.0, 130.0, 300.0);
+        let (rgb, status) = pixel.to_rgb_report();
+
+        assert!(rgb.is_in_gamut(Gamut::Srgb));
+        match status {
+            GamutStatus::Clipped { channel, amount } => {
+                // the amount is how far the unclamped channel went past `1.0`
+                assert_eq!(channel, Channel::Blue);
+                assert!(amount > 0.1 && (amount - (pixel.as_rgb().2 - 1.0)).abs() < 1e-6, "{}", amount);
+            }
+            GamutStatus::InGamut => panic!("Expected the colour to be clipped"),
+        }
+    }
+
+    #[test]
+    fn reports_in_gamut() {
+        let reports = LchPixel::to_rgb_reports(&[
+            LchPixel(50.0, 20.0, 30.0),
+            LchPixel(90.0, 100.0, 140.0),
+        ]);
+
+        assert_eq!(reports[0].1, GamutStatus::InGamut);
+        assert!(matches!(reports[1].1, GamutStatus::Clipped { channel: Channel::Green, .. }));
+    }
+
+    #[test]
+    fn try_add_reports_clamping() {
+        let mut pixel = LchPixel(50.0, 140.0, 120.0);
+
+        assert!(pixel.try_add_chroma(5.0).is_ok());
+        assert_eq!(pixel.1, 145.0);
+
+        assert_eq!(pixel.try_add_chroma(10.0).unwrap_err(), ClampedError { requested: 155.0, clamped: 150.0 });
+        assert_eq!(pixel.1, 145.0);
+
+        assert!(pixel.try_add_luma(-60.0).is_err());
+        assert!(pixel.try_add_luma(10.0).unwrap().try_add_chroma(-5.0).is_ok());
+        assert_eq!(pixel.get(), (60.0, 140.0, 120.0));
+    }
+
+    #[test]
+    fn round_to_keeps_achromatic_hue() {
+        let rounded = LchPixel(53.240_59, 104.551_76, f32::NAN).round_to(2);
+
+        assert_eq!((rounded.0, rounded.1), (53.24, 104.55));
+        assert!(rounded.2.is_nan());
+    }
+}
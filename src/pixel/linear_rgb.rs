@@ -0,0 +1,92 @@
+use std::ops::RangeInclusive;
+
+use crate::conversions::{linear_rgb_to_srgb, srgb_to_linear_rgb};
+
+use super::{rgb::RgbPixel, round_decimals};
+
+#[derive(Debug, Clone, Copy, Default)]
+/// Represents a pixel in *linear* RGB - light intensities, like the output of a renderer, rather than
+/// the encoded (gamma-corrected) values stored in images. Each channel ranges between 0.0 and 1.0.
+///
+/// `RgbPixel` is encoded sRGB - `from_rgb` and `as_rgb` remove and apply the transfer function. The other spaces
+/// convert from linear light too, so `LabPixel::from_linear_rgb` gives the same colour as going through `RgbPixel`.
+pub struct LinearRgbPixel(pub f32, pub f32, pub f32);
+
+impl From<(f32, f32, f32)> for LinearRgbPixel {
+    fn from(value: (f32, f32, f32)) -> Self {
+        let (r, g, b) = value;
+        LinearRgbPixel(r, g, b)
+    }
+}
+
+/// Each component is rounded to the nearest `f32`.
+impl From<(f64, f64, f64)> for LinearRgbPixel {
+    fn from(value: (f64, f64, f64)) -> Self {
+        LinearRgbPixel(value.0 as f32, value.1 as f32, value.2 as f32)
+    }
+}
+
+impl LinearRgbPixel {
+    /// The valid range of the red channel.
+    pub const R_RANGE: RangeInclusive<f32> = 0.0..=1.0;
+    /// The valid range of the green channel.
+    pub const G_RANGE: RangeInclusive<f32> = 0.0..=1.0;
+    /// The valid range of the blue channel.
+    pub const B_RANGE: RangeInclusive<f32> = 0.0..=1.0;
+
+    pub fn get(&self) -> (f32, f32, f32) {
+        (self.0, self.1, self.2)
+    }
+
+    /// Rounds each component to `decimals` decimal places. See `RgbPixel::round_to`.
+    #[must_use]
+    pub fn round_to(&self, decimals: u32) -> Self {
+        LinearRgbPixel(round_decimals(self.0, decimals), round_decimals(self.1, decimals), round_decimals(self.2, decimals))
+    }
+
+    /// Decodes an `RgbPixel` (as encoded sRGB) into linear light.
+    pub fn from_rgb(rgb: &RgbPixel) -> LinearRgbPixel {
+        srgb_to_linear_rgb(rgb.get()).into()
+    }
+
+    /// Encodes the pixel as an `RgbPixel` (encoded sRGB).
+    #[must_use]
+    pub fn as_rgb(&self) -> RgbPixel {
+        linear_rgb_to_srgb(self.get()).into()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::pixel::rgb::RgbPixel;
+
+    use super::LinearRgbPixel;
+
+    #[test]
+    fn encoding_round_trips() {
+        let rgb = RgbPixel(0.5, 0.2, 0.9);
+        let linear = LinearRgbPixel::from_rgb(&rgb);
+
+        assert!(linear.0 < rgb.0, "decoding should darken the mid-tones");
+
+        let (r, g, b) = linear.as_rgb().get();
+        assert!((r - 0.5).abs() < 1e-5 && (g - 0.2).abs() < 1e-5 && (b - 0.9).abs() < 1e-5);
+    }
+
+    #[test]
+    fn converts_straight_to_lab_and_oklab() {
+        use crate::pixel::{lab::LabPixel, oklab::OklabPixel};
+
+        for rgb in [RgbPixel(0.8, 0.4, 0.2), RgbPixel(0.02, 0.5, 0.98), RgbPixel(0.0, 0.0, 0.0), RgbPixel(1.0, 1.0, 1.0)] {
+            let linear = LinearRgbPixel::from_rgb(&rgb);
+
+            assert_eq!(LabPixel::from_linear_rgb(&linear).get(), rgb.as_lab().get());
+            assert_eq!(OklabPixel::from_linear_rgb(&linear).get(), rgb.as_oklab().get());
+        }
+
+        // the linear channels differ from the encoded ones, so the shortcut really does skip the decoding
+        let rgb = RgbPixel(0.8, 0.4, 0.2);
+        let same_numbers = LinearRgbPixel(rgb.0, rgb.1, rgb.2);
+        assert!(LabPixel::from_linear_rgb(&same_numbers).0 > rgb.as_lab().0);
+    }
+}
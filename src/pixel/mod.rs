@@ -1,9 +1,17 @@
+use self::{hsl::HslPixel, lab::LabPixel, lch::LchPixel, linear_rgb::LinearRgbPixel, oklab::OklabPixel, oklch::OklchPixel, rgb::RgbPixel, xyz::XyzPixel};
+
 /// Monochromatic pixels. Effectively represent RGB pixels, except each channel is equivalent.
 pub mod mono;
 
 /// RGB pixels. Have 3 components for Red, Green, and Blue.
 pub mod rgb;
 
+/// Linear RGB pixels. RGB pixels in linear light, rather than encoded with the sRGB transfer function.
+pub mod linear_rgb;
+
+/// RGBA pixels. RGB pixels with an extra component for Alpha (opacity).
+pub mod rgba;
+
 /// HSL pixels. Have 3 components for Hue, Saturation, and Luminance.
 pub mod hsl;
 
@@ -15,4 +23,282 @@ pub mod lch;
 
 pub mod oklab;
 
-pub mod oklch;
\ No newline at end of file
+pub mod oklch;
+
+/// YCbCr pixels. Have 3 components for luma (Y), and the blue (Cb) and red (Cr) differences - used by video.
+pub mod ycbcr;
+
+/// XYZ pixels. Have 3 components for X, Y (luminance), and Z - relative to a D65 white.
+pub mod xyz;
+
+/// A pixel in any colour space that can be converted to and from RGB - for writing code that's generic over colour spaces.
+pub trait Pixel: Sized {
+    /// Converts an `RgbPixel` into this colour space.
+    fn from_rgb(rgb: &RgbPixel) -> Self;
+
+    /// Converts the pixel into an `RgbPixel`.
+    fn to_rgb(&self) -> RgbPixel;
+}
+
+impl Pixel for RgbPixel {
+    fn from_rgb(rgb: &RgbPixel) -> Self {
+        *rgb
+    }
+
+    fn to_rgb(&self) -> RgbPixel {
+        *self
+    }
+}
+
+impl Pixel for HslPixel {
+    fn from_rgb(rgb: &RgbPixel) -> Self {
+        HslPixel::from_rgb(rgb)
+    }
+
+    fn to_rgb(&self) -> RgbPixel {
+        self.as_rgb()
+    }
+}
+
+impl Pixel for LabPixel {
+    fn from_rgb(rgb: &RgbPixel) -> Self {
+        LabPixel::from_rgb(rgb)
+    }
+
+    fn to_rgb(&self) -> RgbPixel {
+        self.as_rgb()
+    }
+}
+
+impl Pixel for LchPixel {
+    fn from_rgb(rgb: &RgbPixel) -> Self {
+        LchPixel::from_rgb(rgb)
+    }
+
+    fn to_rgb(&self) -> RgbPixel {
+        self.as_rgb()
+    }
+}
+
+impl Pixel for LinearRgbPixel {
+    fn from_rgb(rgb: &RgbPixel) -> Self {
+        LinearRgbPixel::from_rgb(rgb)
+    }
+
+    fn to_rgb(&self) -> RgbPixel {
+        self.as_rgb()
+    }
+}
+
+impl Pixel for OklabPixel {
+    fn from_rgb(rgb: &RgbPixel) -> Self {
+        OklabPixel::from_rgb(rgb)
+    }
+
+    fn to_rgb(&self) -> RgbPixel {
+        self.as_rgb()
+    }
+}
+
+impl Pixel for OklchPixel {
+    fn from_rgb(rgb: &RgbPixel) -> Self {
+        OklchPixel::from_rgb(rgb)
+    }
+
+    fn to_rgb(&self) -> RgbPixel {
+        self.as_rgb()
+    }
+}
+
+impl Pixel for XyzPixel {
+    fn from_rgb(rgb: &RgbPixel) -> Self {
+        XyzPixel::from_rgb(rgb)
+    }
+
+    fn to_rgb(&self) -> RgbPixel {
+        self.as_rgb()
+    }
+}
+
+/// A pixel in a cylindrical colour space - one with a hue (in degrees) around a colour wheel, like HSL, LCH, and OKLCH.
+///
+/// The hue operations are shared between them, so they all treat the hue the same way - and an achromatic
+/// (`NaN`) hue is left alone, since there's no hue to change.
+pub trait Cylindrical: Sized {
+    /// Retrieves the hue, in degrees.
+    fn hue(&self) -> f32;
+
+    /// Sets the hue, in degrees.
+    fn set_hue(&mut self, hue: f32);
+
+    /// Checks whether the pixel has no hue (it's `NaN`) - which is the case for greys.
+    fn is_achromatic(&self) -> bool {
+        self.hue().is_nan()
+    }
+
+    /// Rotates the hue by `degrees`, keeping it within `0.0~360.0`.
+    fn rotate_hue(&mut self, degrees: f32) -> &mut Self {
+        if !self.is_achromatic() {
+            self.set_hue((self.hue() + degrees).rem_euclid(360.0));
+        }
+        self
+    }
+
+    /// Snaps the hue to the nearest of `hues` - measured around the colour wheel, so `350.0` is nearer
+    /// to `0.0` than to `300.0`. If several are equally near, the first one is picked.
+    ///
+    /// The hue is left as-is if `hues` is empty. Otherwise, it's kept within `0.0~360.0`.
+    fn quantize_hue(&mut self, hues: &[f32]) -> &mut Self {
+        if self.is_achromatic() {
+            return self;
+        }
+
+        let hue = self.hue().rem_euclid(360.0);
+        let distance = |other: f32| {
+            let difference = (other.rem_euclid(360.0) - hue).abs();
+            difference.min(360.0 - difference)
+        };
+
+        let nearest = hues.iter().copied().fold(None, |nearest: Option<f32>, current| match nearest {
+            Some(nearest) if distance(nearest) <= distance(current) => Some(nearest),
+            _ => Some(current),
+        });
+
+        if let Some(nearest) = nearest {
+            self.set_hue(nearest.rem_euclid(360.0));
+        }
+        self
+    }
+}
+
+/// Converts a pixel from one colour space to another, going through RGB:
+///
+/// ```ignore
+/// let oklch: OklchPixel = convert(LabPixel(50.0, 20.0, -30.0));
+/// ```
+///
+/// Converting between spaces that don't need RGB in-between (such as LAB and LCH) can be more precise
+/// with the concrete methods, like `LabPixel::as_lch`.
+pub fn convert<F: Pixel, T: Pixel>(pixel: F) -> T {
+    T::from_rgb(&pixel.to_rgb())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+/// An error for when an adjustment would have been clamped to a component's range.
+pub struct ClampedError {
+    /// The value that was asked for.
+    pub requested: f32,
+    /// The value it would have been clamped to.
+    pub clamped: f32,
+}
+
+impl std::fmt::Display for ClampedError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} is out of range, and would be clamped to {}", self.requested, self.clamped)
+    }
+}
+
+impl std::error::Error for ClampedError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// An error for when a pixel is read from a slice of bytes with the wrong length.
+pub struct InvalidByteLength {
+    /// The number of bytes the pixel needs.
+    pub expected: usize,
+    /// The number of bytes that were given.
+    pub found: usize,
+}
+
+impl std::fmt::Display for InvalidByteLength {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "expected {} bytes, found {}", self.expected, self.found)
+    }
+}
+
+impl std::error::Error for InvalidByteLength {}
+
+/// Rounds a component to `decimals` decimal places.
+///
+/// The rounding is done in `f64` - where scaling an `f32` is exact - so the result is the `f32` nearest to the
+/// rounded decimal, whatever noise was in the last bits. `NaN` (like an achromatic hue) is kept as-is.
+pub(crate) fn round_decimals(value: f32, decimals: u32) -> f32 {
+    let scale = 10f64.powi(decimals.min(i32::MAX as u32) as i32);
+    let scaled = value as f64 * scale;
+    if !scaled.is_finite() {
+        return value;
+    }
+    // adding zero turns a rounded `-0` into `0`
+    ((scaled.round() / scale) as f32) + 0.0
+}
+
+/// Formats a number for CSS - rounded to `decimals` places, without trailing zeros.
+pub(crate) fn css_number(value: f32, decimals: i32) -> String {
+    let scale = 10f32.powi(decimals);
+    // adding zero turns a rounded `-0` into `0`
+    format!("{}", (value * scale).round() / scale + 0.0)
+}
+
+/// Formats a hue for CSS - where an achromatic (`NaN`) hue is `none`.
+pub(crate) fn css_hue(hue: f32) -> String {
+    if hue.is_nan() {
+        String::from("none")
+    } else {
+        css_number(hue, 2)
+    }
+}
+
+/// Asserts that each component of `a` is within `tolerance` of `b` - where two `NaN`s (like achromatic hues) are equal.
+#[cfg(test)]
+pub(crate) fn assert_close(a: (f32, f32, f32), b: (f32, f32, f32), tolerance: f32) {
+    let close = |x: f32, y: f32| (x.is_nan() && y.is_nan()) || (x - y).abs() < tolerance;
+    assert!(close(a.0, b.0) && close(a.1, b.1) && close(a.2, b.2), "{:?} != {:?}", a, b);
+}
+
+#[cfg(test)]
+mod test {
+    use super::{assert_close, convert, hsl::HslPixel, lab::LabPixel, lch::LchPixel, oklab::OklabPixel, oklch::OklchPixel, rgb::RgbPixel, xyz::XyzPixel, Cylindrical, Pixel};
+
+    /// Round-trips through another space, without knowing what it is.
+    fn round_trip<T: Pixel>(rgb: RgbPixel) -> RgbPixel {
+        convert::<T, RgbPixel>(convert::<RgbPixel, T>(rgb))
+    }
+
+    #[test]
+    fn convert_matches_concrete_methods() {
+        let rgb = RgbPixel(0.8, 0.4, 0.2);
+
+        assert_close(convert::<RgbPixel, LabPixel>(rgb).get(), rgb.as_lab().get(), 1e-4);
+        assert_close(convert::<RgbPixel, OklchPixel>(rgb).get(), rgb.as_oklch().get(), 1e-4);
+        assert_close(convert::<LabPixel, OklabPixel>(rgb.as_lab()).get(), rgb.as_lab().as_rgb().as_oklab().get(), 1e-4);
+        assert_close(convert::<HslPixel, XyzPixel>(rgb.as_hsl()).get(), XyzPixel::from_rgb(&rgb.as_hsl().as_rgb()).get(), 1e-4);
+        assert_close(convert::<LchPixel, RgbPixel>(rgb.as_lch()).get(), rgb.as_lch().as_rgb().get(), 1e-4);
+
+        for round_tripped in [round_trip::<HslPixel>(rgb), round_trip::<LabPixel>(rgb), round_trip::<OklchPixel>(rgb), round_trip::<XyzPixel>(rgb)] {
+            assert_close(round_tripped.get(), rgb.get(), 1e-4);
+        }
+    }
+
+    /// Exercises the hue operations, without knowing which cylindrical space the pixel is in.
+    fn check_hue_operations<T: Cylindrical>(mut pixel: T) {
+        pixel.set_hue(350.0);
+        assert_eq!(pixel.quantize_hue(&[0.0, 300.0]).hue(), 0.0);
+
+        pixel.set_hue(10.0);
+        assert_eq!(pixel.quantize_hue(&[-60.0, 90.0]).hue(), 300.0);
+        assert_eq!(pixel.quantize_hue(&[]).hue(), 300.0);
+
+        assert_eq!(pixel.rotate_hue(90.0).hue(), 30.0);
+        assert_eq!(pixel.rotate_hue(-60.0).hue(), 330.0);
+
+        pixel.set_hue(f32::NAN);
+        assert!(pixel.is_achromatic());
+        assert!(pixel.rotate_hue(30.0).quantize_hue(&[0.0]).hue().is_nan());
+    }
+
+    #[test]
+    fn cylindrical_hue_operations() {
+        check_hue_operations(HslPixel(0.0, 0.5, 0.5));
+        check_hue_operations(LchPixel(50.0, 30.0, 0.0));
+        check_hue_operations(OklchPixel(0.5, 0.1, 0.0));
+    }
+}
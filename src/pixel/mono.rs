@@ -1,4 +1,10 @@
-#[derive(Debug, Clone, Copy)]
+use std::ops::RangeInclusive;
+
+use crate::{conversions::{linear_rgb_to_srgb, srgb_to_linear_rgb}, parse::ParseColourError};
+
+use super::{oklab::OklabPixel, rgb::RgbPixel};
+
+#[derive(Debug, Clone, Copy, Default)]
 /// Represents a monochromatic pixel. It only has one value, which effectively represents the luminance.
 pub struct MonoPixel(u8);
 
@@ -10,10 +16,58 @@ impl From<u8> for MonoPixel {
     }
 }
 
+/// Formats the luminance as a decimal (`128`) - or with the alternate flag (`{:#}`), as a hex pair (`#80`).
+impl std::fmt::Display for MonoPixel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if f.alternate() {
+            write!(f, "#{:02x}", self.0)
+        } else {
+            write!(f, "{}", self.0)
+        }
+    }
+}
+
+/// Parses a hex pair with a leading `#` (`#80`), a decimal from `0` to `255` (`128`), or a hex pair without
+/// the `#` (`c0`). Without the `#`, anything that's only decimal digits is read as a decimal - so `80` is `80`, not `0x80`.
+impl std::str::FromStr for MonoPixel {
+    type Err = ParseColourError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let is_decimal = !s.is_empty() && s.chars().all(|digit| digit.is_ascii_digit());
+
+        if is_decimal {
+            return s.parse::<u8>().map(MonoPixel).map_err(|_| ParseColourError::OutOfRange(s.to_string()));
+        }
+
+        let digits = s.strip_prefix('#').unwrap_or(s);
+        if digits.len() != 2 {
+            return Err(ParseColourError::InvalidLength(digits.len()));
+        }
+
+        if let Some(digit) = digits.chars().find(|digit| !digit.is_ascii_hexdigit()) {
+            return Err(ParseColourError::InvalidDigit(digit));
+        }
+
+        Ok(MonoPixel(u8::from_str_radix(digits, 16).expect("both digits are valid hex")))
+    }
+}
+
 impl MonoPixel {
+    /// The valid range of the luminance.
+    pub const L_RANGE: RangeInclusive<u8> = 0..=255;
+
+    /// Creates a pixel from a signed value, clamping it to `0~255` - so negative values become `0` rather than wrapping around.
+    pub fn from_i32_clamped(value: i32) -> MonoPixel {
+        let (min, max) = (*Self::L_RANGE.start() as i32, *Self::L_RANGE.end() as i32);
+        MonoPixel(value.clamp(min, max) as u8)
+    }
+
     /// Adds an error to the luminance of the pixel.
+    #[must_use]
     pub fn add_error(self, error: i32) -> MonoPixel {
-        MonoPixel((self.0 as i32 + error).min(255).max(0) as u8)
+        let (min, max) = (*Self::L_RANGE.start() as i32, *Self::L_RANGE.end() as i32);
+        MonoPixel((self.0 as i32 + error).clamp(min, max) as u8)
     }
 
     /// Quantizes the pixel to the nearest `MonoPixel` in the palette.
@@ -43,4 +97,191 @@ impl MonoPixel {
     pub fn get(&self) -> u8 {
         self.0
     }
+
+    /// Linearly interpolates between `a` and `b`, rounding to the nearest value. See `RgbPixel::lerp`.
+    ///
+    /// Extrapolated values saturate at `0` and `255`.
+    pub fn lerp(a: &MonoPixel, b: &MonoPixel, t: f32) -> MonoPixel {
+        let value = a.0 as f32 + (b.0 as f32 - a.0 as f32) * t;
+        MonoPixel(value.round().clamp(0.0, 255.0) as u8)
+    }
+
+    /// Mixes the pixel with another in linear light - where a `ratio` of `0.0` returns this pixel, and `1.0` returns `other`.
+    ///
+    /// The values are treated as encoded sRGB (like the bytes of a greyscale image), so they're decoded before
+    /// mixing and re-encoded after - a half-and-half mix of black and white is `188`, which looks like a middle
+    /// grey, rather than the darker `128`. The maths is done in `f32` and only rounded at the end. The ratio is clamped.
+    #[must_use]
+    pub fn mix(&self, other: &MonoPixel, ratio: f32) -> MonoPixel {
+        let decode = |pixel: &MonoPixel| srgb_to_linear_rgb((pixel.0 as f32 / 255.0, 0.0, 0.0)).0;
+        let ratio = ratio.clamp(0.0, 1.0);
+
+        let linear = decode(self) + (decode(other) - decode(self)) * ratio;
+        let encoded = linear_rgb_to_srgb((linear, 0.0, 0.0)).0;
+        MonoPixel((encoded * 255.0).round().clamp(0.0, 255.0) as u8)
+    }
+
+    /// Scales the luminance by a factor, saturating at `0` and `255`.
+    #[must_use]
+    pub fn scale(&self, factor: f32) -> MonoPixel {
+        MonoPixel((self.0 as f32 * factor).round().clamp(0.0, 255.0) as u8)
+    }
+
+    /// Inverts the pixel (`255 - value`) - black becomes white, and vice versa.
+    #[must_use]
+    pub fn invert(&self) -> MonoPixel {
+        MonoPixel(u8::MAX - self.0)
+    }
+
+    /// Generates a ramp of `count` greys from black to white, evenly spaced in OKLAB lightness - so each step
+    /// looks equally far apart, which is useful for testing displays.
+    ///
+    /// The values are encoded sRGB, like `mix` expects. That encoding is already close to perceptual, so the
+    /// bytes are nearly evenly spaced - only the darkest steps are smaller. A `count` of `1` is just black.
+    pub fn perceptual_ramp(count: usize) -> Vec<MonoPixel> {
+        let step = 1.0 / count.saturating_sub(1).max(1) as f32;
+
+        (0..count)
+            .map(|i| {
+                let grey = OklabPixel(i as f32 * step, 0.0, 0.0).as_rgb().0;
+                MonoPixel((grey * 255.0).round().clamp(0.0, 255.0) as u8)
+            })
+            .collect()
+    }
+
+    /// Converts the pixel to a grey `RgbPixel`.
+    #[must_use]
+    pub fn as_rgb(&self) -> RgbPixel {
+        let value = self.0 as f32 / 255.0;
+        RgbPixel(value, value, value)
+    }
+
+    /// Tints the pixel with the hue and chroma of another colour, while keeping its own lightness (in OKLAB).
+    ///
+    /// The result is gamut-mapped - so black and white stay black and white.
+    pub fn colorize(&self, tint: &RgbPixel) -> RgbPixel {
+        self.as_rgb().colorize(tint)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::pixel::rgb::colours::{BLACK, RED, WHITE};
+
+    use crate::parse::ParseColourError;
+
+    use super::MonoPixel;
+
+    #[test]
+    fn add_error_saturates_at_range() {
+        assert_eq!(MonoPixel(200).add_error(1000).get(), *MonoPixel::L_RANGE.end());
+        assert_eq!(MonoPixel(50).add_error(-1000).get(), *MonoPixel::L_RANGE.start());
+    }
+
+    #[test]
+    fn from_i32_clamped_saturates() {
+        assert_eq!(MonoPixel::from_i32_clamped(-1).get(), 0);
+        assert_eq!(MonoPixel::from_i32_clamped(300).get(), 255);
+        assert_eq!(MonoPixel::from_i32_clamped(128).get(), 128);
+    }
+
+    #[test]
+    fn colorize_keeps_lightness() {
+        let grey = MonoPixel(128);
+        let tinted = grey.colorize(&RED);
+
+        assert!((tinted.as_oklab().0 - grey.as_rgb().as_oklab().0).abs() < 1e-3);
+        assert!((tinted.as_oklch().2 - RED.as_oklch().2).abs() < 1.0);
+        assert!(tinted.0 > tinted.1 && tinted.0 > tinted.2);
+    }
+
+    #[test]
+    fn colorize_keeps_black_and_white() {
+        assert_eq!(MonoPixel(0).colorize(&RED).get_u8(), BLACK.get_u8());
+        assert_eq!(MonoPixel(255).colorize(&RED).get_u8(), WHITE.get_u8());
+    }
+
+    #[test]
+    fn lerp_hits_endpoints() {
+        let (a, b) = (MonoPixel(10), MonoPixel(200));
+
+        assert_eq!(MonoPixel::lerp(&a, &b, 0.0).get(), 10);
+        assert_eq!(MonoPixel::lerp(&a, &b, 1.0).get(), 200);
+        assert_eq!(MonoPixel::lerp(&a, &b, 2.0).get(), 255);
+    }
+
+    #[test]
+    fn perceptual_ramp_spans_black_to_white() {
+        let ramp: Vec<u8> = MonoPixel::perceptual_ramp(11).iter().map(MonoPixel::get).collect();
+
+        assert_eq!(ramp.len(), 11);
+        assert_eq!((ramp[0], ramp[10]), (0, 255));
+        // the darkest steps can round to the same byte
+        assert!(ramp.windows(2).all(|pair| pair[0] <= pair[1]), "{:?}", ramp);
+
+        let (first_step, last_step) = (ramp[1] - ramp[0], ramp[10] - ramp[9]);
+        assert!(first_step < last_step, "{:?}", ramp);
+
+        assert_eq!(MonoPixel::perceptual_ramp(1).len(), 1);
+        assert!(MonoPixel::perceptual_ramp(0).is_empty());
+    }
+
+    #[test]
+    fn perceptual_ramp_agrees_with_mix() {
+        let (black, white) = (MonoPixel(0), MonoPixel(255));
+
+        // for a grey, OKLAB lightness is the cube root of the linear luminance
+        for (i, grey) in MonoPixel::perceptual_ramp(11).iter().enumerate() {
+            let luminance = (i as f32 / 10.0).powi(3);
+            let mixed = black.mix(&white, luminance).get();
+            assert!(mixed.abs_diff(grey.get()) <= 1, "step {}: {} vs {}", i, mixed, grey.get());
+        }
+    }
+
+    #[test]
+    fn mix_is_linear_light() {
+        let (black, white) = (MonoPixel(0), MonoPixel(255));
+
+        assert_eq!(black.mix(&white, 0.5).get(), 188);
+        assert_eq!(black.mix(&white, 0.0).get(), 0);
+        assert_eq!(black.mix(&white, 1.0).get(), 255);
+    }
+
+    #[test]
+    fn scale_saturates() {
+        assert_eq!(MonoPixel(100).scale(0.5).get(), 50);
+        assert_eq!(MonoPixel(100).scale(3.0).get(), 255);
+        assert_eq!(MonoPixel(100).scale(-1.0).get(), 0);
+    }
+
+    #[test]
+    fn invert_is_self_inverse() {
+        for value in 0..=255 {
+            let pixel = MonoPixel(value);
+            assert_eq!(pixel.invert().invert().get(), value);
+        }
+
+        assert_eq!(MonoPixel(0).invert().get(), 255);
+    }
+
+    #[test]
+    fn display_round_trips() {
+        for value in [0, 7, 128, 200, 255] {
+            let pixel = MonoPixel(value);
+
+            assert_eq!(pixel.to_string().parse::<MonoPixel>().map(|pixel| pixel.get()), Ok(value));
+            assert_eq!(format!("{:#}", pixel).parse::<MonoPixel>().map(|pixel| pixel.get()), Ok(value));
+        }
+
+        assert_eq!(format!("{:#}", MonoPixel(128)), "#80");
+        assert_eq!("c0".parse::<MonoPixel>().map(|pixel| pixel.get()), Ok(192));
+    }
+
+    #[test]
+    fn parse_rejects_invalid_values() {
+        assert_eq!("256".parse::<MonoPixel>().unwrap_err(), ParseColourError::OutOfRange(String::from("256")));
+        assert_eq!("#fff".parse::<MonoPixel>().unwrap_err(), ParseColourError::InvalidLength(3));
+        assert_eq!("#gg".parse::<MonoPixel>().unwrap_err(), ParseColourError::InvalidDigit('g'));
+        assert!("-1".parse::<MonoPixel>().is_err());
+    }
 }
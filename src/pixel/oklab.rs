@@ -1,8 +1,10 @@
-use crate::conversions::{chain_conversions, rgb_to_xyz_d65, xyz_d65_to_oklab, oklab_to_xyz_d65, xyz_d65_to_rgb};
+use std::ops::RangeInclusive;
 
-use super::{rgb::RgbPixel, oklch::OklchPixel};
+use crate::{comparisons::delta_e_ok, conversions::{chain_conversions, linear_rgb_to_srgb, srgb_to_linear_rgb, rgb_to_xyz_d65, xyz_d65_to_oklab, oklab_to_xyz_d65, xyz_d65_to_rgb}};
 
-#[derive(Debug, Clone, Copy)]
+use super::{linear_rgb::LinearRgbPixel, rgb::RgbPixel, oklch::OklchPixel, round_decimals};
+
+#[derive(Debug, Clone, Copy, Default)]
 /// The 3 components of an OKLAB pixel are:
 /// 
 /// - L: Ranges from 0.0 to 1.0. Determines the visible luminance of the pixel.
@@ -20,26 +22,189 @@ impl From<(f32, f32, f32)> for OklabPixel {
     }
 }
 
+/// Each component is rounded to the nearest `f32`.
+impl From<(f64, f64, f64)> for OklabPixel {
+    fn from(value: (f64, f64, f64)) -> Self {
+        OklabPixel(value.0 as f32, value.1 as f32, value.2 as f32)
+    }
+}
+
+/// Adds the components together. See `LabPixel`'s `Add`.
+impl std::ops::Add for OklabPixel {
+    type Output = OklabPixel;
+
+    fn add(self, rhs: OklabPixel) -> Self::Output {
+        OklabPixel(self.0 + rhs.0, self.1 + rhs.1, self.2 + rhs.2)
+    }
+}
+
+/// Subtracts the components. See `LabPixel`'s `Sub`.
+impl std::ops::Sub for OklabPixel {
+    type Output = OklabPixel;
+
+    fn sub(self, rhs: OklabPixel) -> Self::Output {
+        OklabPixel(self.0 - rhs.0, self.1 - rhs.1, self.2 - rhs.2)
+    }
+}
+
+/// Scales every component by `rhs`. See `LabPixel`'s `Mul`.
+impl std::ops::Mul<f32> for OklabPixel {
+    type Output = OklabPixel;
+
+    fn mul(self, rhs: f32) -> Self::Output {
+        OklabPixel(self.0 * rhs, self.1 * rhs, self.2 * rhs)
+    }
+}
+
 impl OklabPixel {
+    /// The valid range of the luma.
+    pub const L_RANGE: RangeInclusive<f32> = 0.0..=1.0;
+    /// The (rough) range of the green-red axis.
+    pub const A_RANGE: RangeInclusive<f32> = -0.4..=0.4;
+    /// The (rough) range of the blue-yellow axis.
+    pub const B_RANGE: RangeInclusive<f32> = -0.4..=0.4;
+
     pub fn get(&self) -> (f32, f32, f32) {
         (self.0, self.1, self.2)
     }
 
+    /// Rounds each component to `decimals` decimal places. See `RgbPixel::round_to`.
+    #[must_use]
+    pub fn round_to(&self, decimals: u32) -> Self {
+        OklabPixel(round_decimals(self.0, decimals), round_decimals(self.1, decimals), round_decimals(self.2, decimals))
+    }
+
+    /// Linearly interpolates between `a` and `b`. See `RgbPixel::lerp`.
+    pub fn lerp(a: &OklabPixel, b: &OklabPixel, t: f32) -> OklabPixel {
+        let lerp = |start: f32, end: f32| start + (end - start) * t;
+        OklabPixel(lerp(a.0, b.0), lerp(a.1, b.1), lerp(a.2, b.2))
+    }
+
+    /// Retrieves the angle of the (a, b) chroma vector in degrees. See `LabPixel::chroma_angle`.
+    pub fn chroma_angle(&self) -> f32 {
+        self.2.atan2(self.1).to_degrees().rem_euclid(360.0)
+    }
+
+    /// Retrieves the length of the (a, b) chroma vector - the same as the OKLCH chroma.
+    pub fn chroma_magnitude(&self) -> f32 {
+        self.1.hypot(self.2)
+    }
+
+    /// Rotates the (a, b) chroma vector by `degrees`. See `LabPixel::rotate_ab`.
+    #[must_use]
+    pub fn rotate_ab(&self, degrees: f32) -> OklabPixel {
+        let (sin, cos) = degrees.to_radians().sin_cos();
+        OklabPixel(self.0, self.1 * cos - self.2 * sin, self.1 * sin + self.2 * cos)
+    }
+
+    /// Clamps each component to its range (see `L_RANGE`, `A_RANGE`, and `B_RANGE`), like `RgbPixel::clamp`.
+    #[must_use]
+    pub fn clamp(&self) -> OklabPixel {
+        OklabPixel(
+            self.0.clamp(*Self::L_RANGE.start(), *Self::L_RANGE.end()),
+            self.1.clamp(*Self::A_RANGE.start(), *Self::A_RANGE.end()),
+            self.2.clamp(*Self::B_RANGE.start(), *Self::B_RANGE.end()),
+        )
+    }
+
+    /// Checks whether each component is within its range.
+    pub fn is_valid(&self) -> bool {
+        Self::L_RANGE.contains(&self.0) && Self::A_RANGE.contains(&self.1) && Self::B_RANGE.contains(&self.2)
+    }
+
+    /// Retrieves the distance between two colours, as the euclidean distance in OKLAB (ΔE OK).
+    pub fn distance_from(&self, other: &OklabPixel) -> f32 {
+        delta_e_ok(self.get(), other.get())
+    }
+
+    pub fn quantize(&self, palette: &[OklabPixel]) -> OklabPixel {
+        let mut closest_distance = f32::MAX;
+        let mut current_colour = self;
+
+        for colour in palette.iter() {
+            let distance = colour.distance_from(self);
+            if distance < closest_distance {
+                current_colour = colour;
+                closest_distance = distance;
+            };
+        }
+
+        current_colour.get().into()
+    }
+
     pub fn from_rgb(rgb: &RgbPixel) -> OklabPixel {
         chain_conversions(rgb.get(), &[
+            srgb_to_linear_rgb,
+            rgb_to_xyz_d65,
+            xyz_d65_to_oklab,
+        ]).into()
+    }
+
+    /// Converts linear light straight to OKLAB - without going through an encoded `RgbPixel` first.
+    ///
+    /// This is the same conversion as `as_oklab` after decoding - `from_linear_rgb(&LinearRgbPixel::from_rgb(&rgb))`
+    /// equals `rgb.as_oklab()`.
+    pub fn from_linear_rgb(linear: &LinearRgbPixel) -> OklabPixel {
+        chain_conversions(linear.get(), &[
             rgb_to_xyz_d65,
             xyz_d65_to_oklab,
         ]).into()
     }
 
+    #[must_use]
     pub fn as_rgb(&self) -> RgbPixel {
         chain_conversions(self.get(), &[
             oklab_to_xyz_d65,
             xyz_d65_to_rgb,
+            linear_rgb_to_srgb,
         ]).into()
     }
 
+    #[must_use]
     pub fn as_oklch(&self) -> OklchPixel {
         OklchPixel::from_oklab(self)
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod test {
+    use super::OklabPixel;
+
+    #[test]
+    fn clamp_brings_components_into_range() {
+        let pixel = OklabPixel(-0.1, 0.5, -0.2);
+        assert!(!pixel.is_valid());
+
+        let clamped = pixel.clamp();
+        assert!(clamped.is_valid());
+        assert_eq!(clamped.get(), (0.0, 0.4, -0.2));
+
+        let valid = OklabPixel(0.5, 0.1, -0.1);
+        assert!(valid.is_valid());
+        assert_eq!(valid.clamp().get(), valid.get());
+    }
+
+    #[test]
+    fn quantize_picks_nearest() {
+        let palette = [OklabPixel(0.2, 0.0, 0.0), OklabPixel(0.6, 0.1, 0.0), OklabPixel(0.9, 0.0, 0.1)];
+
+        assert_eq!(OklabPixel(0.55, 0.08, 0.02).quantize(&palette).get(), palette[1].get());
+        assert!((palette[0].distance_from(&palette[1]) - (0.4_f32.powi(2) + 0.1_f32.powi(2)).sqrt()).abs() < 1e-6);
+    }
+
+    #[test]
+    fn vector_maths() {
+        let rotated = OklabPixel(0.6, 0.1, 0.0).rotate_ab(90.0);
+        assert!(rotated.1.abs() < 1e-6 && (rotated.2 - 0.1).abs() < 1e-6, "{:?}", rotated);
+        assert!((rotated.chroma_angle() - 90.0).abs() < 1e-4);
+
+        let pixel = OklabPixel(0.7, -0.08, 0.12);
+        let oklch = pixel.as_oklch();
+        assert!((pixel.chroma_magnitude() - oklch.1).abs() < 1e-6);
+        assert!((pixel.chroma_angle() - oklch.2).abs() < 1e-3);
+        assert!((pixel.rotate_ab(-400.0).chroma_magnitude() - oklch.1).abs() < 1e-6);
+
+        let (l, a, b) = (pixel + OklabPixel(0.1, 0.02, -0.02) * 2.0 - pixel).get();
+        assert!((l - 0.2).abs() < 1e-6 && (a - 0.04).abs() < 1e-6 && (b + 0.04).abs() < 1e-6);
+    }
+}
@@ -1,8 +1,10 @@
-use crate::{comparisons::cie94, conversions::{oklab_to_oklch, oklch_to_oklab}};
+use std::ops::RangeInclusive;
 
-use super::{oklab::OklabPixel, rgb::RgbPixel};
+use crate::{comparisons::delta_e_ok, conversions::{lerp_hue, oklab_to_oklch, oklch_to_oklab, snap_hue}};
 
-#[derive(Debug, Clone, Copy)]
+use super::{oklab::OklabPixel, rgb::{Channel, Gamut, GamutStatus, RgbPixel, Sign}, ClampedError, Cylindrical, css_hue, css_number, round_decimals};
+
+#[derive(Debug, Clone, Copy, Default)]
 /// The 3 components of an OKLCH pixel are as follows:
 /// 
 /// - Lightness: Ranges from 0.0 to 1.0. Determines the visible luminance of the pixel.
@@ -10,6 +12,16 @@ use super::{oklab::OklabPixel, rgb::RgbPixel};
 /// - Hue: Ranges from 0.0 to 360.0.
 pub struct OklchPixel(pub f32, pub f32, pub f32);
 
+impl Cylindrical for OklchPixel {
+    fn hue(&self) -> f32 {
+        self.2
+    }
+
+    fn set_hue(&mut self, hue: f32) {
+        self.2 = hue;
+    }
+}
+
 impl From<(f32, f32, f32)> for OklchPixel {
     fn from(value: (f32, f32, f32)) -> Self {
         let (l, c, h) = value;
@@ -17,46 +29,157 @@ impl From<(f32, f32, f32)> for OklchPixel {
     }
 }
 
+/// Each component is rounded to the nearest `f32`.
+impl From<(f64, f64, f64)> for OklchPixel {
+    fn from(value: (f64, f64, f64)) -> Self {
+        OklchPixel(value.0 as f32, value.1 as f32, value.2 as f32)
+    }
+}
+
 impl OklchPixel {
+    /// The valid range of the luma.
+    pub const L_RANGE: RangeInclusive<f32> = 0.0..=1.0;
+    /// The (rough) range of the chroma.
+    pub const C_RANGE: RangeInclusive<f32> = 0.0..=0.4;
+    /// The range of the hue. Hue wraps around, so any value is technically valid.
+    pub const H_RANGE: RangeInclusive<f32> = 0.0..=360.0;
+
     pub fn get(&self) -> (f32, f32, f32) {
         (self.0, self.1, self.2)
     }
 
+    /// Rounds each component to `decimals` decimal places. See `RgbPixel::round_to`.
+    #[must_use]
+    pub fn round_to(&self, decimals: u32) -> Self {
+        OklchPixel(round_decimals(self.0, decimals), round_decimals(self.1, decimals), round_decimals(self.2, decimals))
+    }
+
+    /// Linearly interpolates between `a` and `b`, with the hue taking the shortest way around. See `RgbPixel::lerp`.
+    pub fn lerp(a: &OklchPixel, b: &OklchPixel, t: f32) -> OklchPixel {
+        let lerp = |start: f32, end: f32| start + (end - start) * t;
+        OklchPixel(lerp(a.0, b.0), lerp(a.1, b.1), lerp_hue(a.2, b.2, t))
+    }
+
     pub fn add_luma(&mut self, luma: f32) -> &mut Self {
-        self.0 = (self.0 + luma).clamp(0.0, 1.0);
+        self.0 = (self.0 + luma).clamp(*Self::L_RANGE.start(), *Self::L_RANGE.end());
         self
     }
 
+    /// Adds luma, returning a new pixel. Clamped the same way as `add_luma`.
+    #[must_use]
+    pub fn adjusted_luma(&self, luma: f32) -> Self {
+        let mut pixel = *self;
+        pixel.add_luma(luma);
+        pixel
+    }
+
+    /// Adds luma like `add_luma` - except if it would have to be clamped, an error is returned
+    /// (and the pixel is left untouched) instead.
+    pub fn try_add_luma(&mut self, luma: f32) -> Result<&mut Self, ClampedError> {
+        let requested = self.0 + luma;
+        if !Self::L_RANGE.contains(&requested) {
+            let clamped = requested.clamp(*Self::L_RANGE.start(), *Self::L_RANGE.end());
+            return Err(ClampedError { requested, clamped });
+        }
+
+        self.0 = requested;
+        Ok(self)
+    }
+
     pub fn add_chroma(&mut self, chroma: f32) -> &mut Self {
-        self.1 = (self.1 + chroma).clamp(0.0, 0.4);
+        self.1 = (self.1 + chroma).clamp(*Self::C_RANGE.start(), *Self::C_RANGE.end());
         self
     }
 
+    /// Adds chroma, returning a new pixel. Clamped the same way as `add_chroma`.
+    #[must_use]
+    pub fn adjusted_chroma(&self, chroma: f32) -> Self {
+        let mut pixel = *self;
+        pixel.add_chroma(chroma);
+        pixel
+    }
+
+    /// Adds chroma like `add_chroma` - except if it would have to be clamped, an error is returned
+    /// (and the pixel is left untouched) instead. Useful for catching runaway chroma in generated palettes.
+    pub fn try_add_chroma(&mut self, chroma: f32) -> Result<&mut Self, ClampedError> {
+        let requested = self.1 + chroma;
+        if !Self::C_RANGE.contains(&requested) {
+            let clamped = requested.clamp(*Self::C_RANGE.start(), *Self::C_RANGE.end());
+            return Err(ClampedError { requested, clamped });
+        }
+
+        self.1 = requested;
+        Ok(self)
+    }
+
     pub fn add_hue(&mut self, hue: f32) -> &mut Self {
         self.2 = self.2 + hue;
         self
     }
 
-    pub fn quantize_hue(&mut self, hues: &[f32]) -> &mut Self {
-        let mut closest_dist = f32::MAX;
-        let pixel_hue = ((self.2 % 360.0) + 360.0) % 360.0;
-        let mut current_hue = pixel_hue;
-
-        for hue in hues.iter() {
-            let normalized = ((hue % 360.0) + 360.0) % 360.0;
-            let distance = (normalized - pixel_hue).abs();
-            if distance < closest_dist {
-                closest_dist = distance;
-                current_hue = normalized;
-            }
-        }
+    /// Adds (rotates) the hue, returning a new pixel.
+    ///
+    /// Unlike `add_hue`, the original pixel is left untouched - so the adjustments can be chained:
+    ///
+    /// ```
+    /// use colour_exercise_rs::pixel::oklch::OklchPixel;
+    ///
+    /// let pixel = OklchPixel(0.5, 0.1, 30.0);
+    /// let adjusted = pixel.adjusted_hue(30.0).adjusted_luma(0.2).adjusted_chroma(-0.05);
+    ///
+    /// assert_eq!(pixel.get(), (0.5, 0.1, 30.0));
+    /// assert!((adjusted.2 - 60.0).abs() < 1e-5);
+    /// assert!((adjusted.0 - 0.7).abs() < 1e-5);
+    /// ```
+    #[must_use]
+    pub fn adjusted_hue(&self, hue: f32) -> Self {
+        let mut pixel = *self;
+        pixel.add_hue(hue);
+        pixel
+    }
 
-        self.2 = current_hue;
+    /// Snaps the hue to the nearest of `n` evenly-spaced hues, starting at `offset` - without building the list
+    /// for `quantize_hue`. The distance is measured around the colour wheel. An `n` of `0` leaves the hue as-is.
+    pub fn quantize_hue_n(&mut self, n: u16, offset: f32) -> &mut Self {
+        self.2 = snap_hue(self.2, n, offset);
         self
     }
 
+    /// Snaps the hue to the nearest of `hues`, measured around the colour wheel. See `Cylindrical::quantize_hue`.
+    pub fn quantize_hue(&mut self, hues: &[f32]) -> &mut Self {
+        Cylindrical::quantize_hue(self, hues)
+    }
+
+    /// Mixes two colours together to produce a third colour - same as `RgbPixel::mix`.
+    ///
+    /// The hue is interpolated along the shortest arc, so mixing `350.0` and `10.0` passes through `0.0`.
+    /// If either colour is achromatic (its hue is `NaN`), the other colour's hue is used.
+    pub fn mix(&self, ratio: f32, other: &OklchPixel) -> Self {
+        let ratio = ratio.clamp(0.0, 1.0);
+        let mix_calc = |chan1: f32, chan2: f32| (chan1 * ratio) + chan2 * (1.0 - ratio);
+
+        OklchPixel(mix_calc(self.0, other.0), mix_calc(self.1, other.1), lerp_hue(other.2, self.2, ratio))
+    }
+
+    /// Mixes two colours around the colour wheel, keeping the blend vivid. The ratio works the same way as in `mix`.
+    ///
+    /// Mixing in OKLAB cuts straight across the colour wheel - so complementary colours pass through grey.
+    /// Instead, the chroma bulges towards the higher of the two colours' chroma, reaching it at the midpoint,
+    /// while the endpoints themselves are kept. The result can be outside of a gamut - see `gamut_map`.
+    pub fn mix_arc(&self, other: &OklchPixel, ratio: f32) -> Self {
+        let ratio = ratio.clamp(0.0, 1.0);
+        let mut mixed = self.mix(ratio, other);
+
+        let peak = self.1.max(other.1);
+        let bulge = (ratio * std::f32::consts::PI).sin();
+        mixed.1 += (peak - mixed.1) * bulge;
+
+        mixed
+    }
+
+    /// Retrieves the distance between two colours, as the euclidean distance in OKLAB (ΔE OK).
     pub fn distance_from(&self, other: &OklchPixel) -> f32 {
-        cie94(self.get(), other.get())
+        delta_e_ok(self.as_oklab().get(), other.as_oklab().get())
     }
 
     pub fn quantize(&self, palette: &[OklchPixel]) -> OklchPixel {
@@ -82,11 +205,190 @@ impl OklchPixel {
         rgb.as_oklab().as_oklch()
     }
 
+    #[must_use]
     pub fn as_oklab(&self) -> OklabPixel {
         oklch_to_oklab(self.get()).into()
     }
 
+    /// Formats the pixel as a CSS Color 4 `oklch()` colour - such as `oklch(70% 0.15 30)`.
+    ///
+    /// The lightness is a percentage, and an achromatic (`NaN`) hue is written as `none`.
+    #[must_use]
+    pub fn to_css(&self) -> String {
+        format!("oklch({}% {} {})", css_number(self.0 * 100.0, 2), css_number(self.1, 4), css_hue(self.2))
+    }
+
+    #[must_use]
     pub fn as_rgb(&self) -> RgbPixel {
         self.as_oklab().as_rgb()
     }
-}
\ No newline at end of file
+
+    /// Checks whether the pixel can be displayed within a gamut without clamping.
+    pub fn is_in_gamut(&self, gamut: Gamut) -> bool {
+        self.as_rgb().is_in_gamut(gamut)
+    }
+
+    /// Converts the pixel to RGB, reducing the chroma until it fits within the gamut.
+    ///
+    /// Unlike clamping the RGB channels, this keeps the lightness and hue intact - only the
+    /// saturation is lost.
+    ///
+    /// The result is always an (sRGB) `RgbPixel`, and only `Gamut::Srgb` clamps it. For the wider gamuts, any colour
+    /// that's outside of sRGB keeps channels outside of `0.0` to `1.0` - they only fit on a wide-gamut display.
+    pub fn gamut_map(&self, gamut: Gamut) -> RgbPixel {
+        if self.is_in_gamut(gamut) {
+            return self.as_rgb();
+        }
+
+        let (low, _) = self.gamut_chroma_bracket(gamut);
+        let mapped = OklchPixel(self.0, low, self.2).as_rgb();
+        match gamut {
+            Gamut::Srgb => mapped.clamp(),
+            _ => mapped,
+        }
+    }
+
+    /// Explains why the pixel is outside of the sRGB gamut - the channel (and the side of its range) that limits
+    /// the chroma. That's the channel that `gamut_map` runs into as it reduces the chroma, and the last one
+    /// to come back into range.
+    ///
+    /// Retrieves `None` if the pixel is already within the gamut.
+    pub fn gamut_limit(&self) -> Option<(Channel, Sign)> {
+        if self.is_in_gamut(Gamut::Srgb) {
+            return None;
+        }
+
+        let (_, high) = self.gamut_chroma_bracket(Gamut::Srgb);
+        // just past the boundary, only the limiting channel is out of range
+        let boundary = OklchPixel(self.0, high, self.2).as_rgb();
+        let GamutStatus::Clipped { channel, .. } = boundary.gamut_status() else {
+            return None;
+        };
+
+        let value = match channel {
+            Channel::Red => boundary.0,
+            Channel::Green => boundary.1,
+            Channel::Blue => boundary.2,
+        };
+
+        Some((channel, if value > 1.0 { Sign::Positive } else { Sign::Negative }))
+    }
+
+    /// Bisects the chroma (at the pixel's lightness and hue) down to the edge of the gamut - returning the chroma
+    /// just inside it, and just outside it. The pixel should be outside of the gamut.
+    fn gamut_chroma_bracket(&self, gamut: Gamut) -> (f32, f32) {
+        const ITERATIONS: usize = 24;
+
+        let (mut low, mut high) = (0.0, self.1);
+        for _ in 0..ITERATIONS {
+            let chroma = (low + high) / 2.0;
+            if OklchPixel(self.0, chroma, self.2).is_in_gamut(gamut) {
+                low = chroma;
+            } else {
+                high = chroma;
+            }
+        }
+
+        (low, high)
+    }
+
+    /// Converts the pixel to a clamped `RgbPixel`, alongside whether clamping was needed (and by how much).
+    pub fn to_rgb_report(&self) -> (RgbPixel, GamutStatus) {
+        let rgb = self.as_rgb();
+        (rgb.clamp(), rgb.gamut_status())
+    }
+
+    /// Runs `to_rgb_report` over an entire palette.
+    pub fn to_rgb_reports(palette: &[OklchPixel]) -> Vec<(RgbPixel, GamutStatus)> {
+        palette.iter().map(|pixel| pixel.to_rgb_report()).collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::pixel::{oklab::OklabPixel, rgb::{colours, Channel, Gamut, Sign}};
+
+    use super::OklchPixel;
+
+    #[test]
+    fn add_saturates_at_ranges() {
+        let mut pixel = OklchPixel(0.5, 0.1, 0.0);
+
+        pixel.add_luma(10.0).add_chroma(10.0);
+        assert_eq!((pixel.0, pixel.1), (*OklchPixel::L_RANGE.end(), *OklchPixel::C_RANGE.end()));
+
+        pixel.add_luma(-10.0).add_chroma(-10.0);
+        assert_eq!((pixel.0, pixel.1), (*OklchPixel::L_RANGE.start(), *OklchPixel::C_RANGE.start()));
+    }
+
+    #[test]
+    fn gamut_map_fits_each_gamut() {
+        let saturated = OklchPixel(0.7, 0.4, 150.0);
+
+        for gamut in [Gamut::Srgb, Gamut::DisplayP3, Gamut::Rec2020] {
+            assert!(!saturated.is_in_gamut(gamut));
+            assert!(saturated.gamut_map(gamut).is_in_gamut(gamut));
+        }
+
+        let srgb = saturated.gamut_map(Gamut::Srgb).as_oklch().1;
+        let p3 = saturated.gamut_map(Gamut::DisplayP3).as_oklch().1;
+        assert!(p3 > srgb);
+    }
+
+    #[test]
+    fn lerp_hits_endpoints() {
+        let (a, b) = (OklchPixel(0.5, 0.1, 350.0), OklchPixel(0.7, 0.2, 30.0));
+
+        assert_eq!(OklchPixel::lerp(&a, &b, 0.0).get(), a.get());
+        assert!((OklchPixel::lerp(&a, &b, 1.0).2 - b.2).abs() < 1e-3);
+        assert!((OklchPixel::lerp(&a, &b, 0.25).2).abs() < 1e-3);
+    }
+
+    #[test]
+    fn distance_is_scaled_for_oklch() {
+        use crate::comparisons::cie94;
+
+        let target = OklchPixel(0.6, 0.1, 30.0);
+        // a small hue shift is much closer than a large jump in lightness
+        let (hue_shifted, lighter) = (OklchPixel(0.6, 0.1, 40.0), OklchPixel(0.9, 0.1, 30.0));
+
+        assert!(cie94(hue_shifted.get(), target.get()) > cie94(lighter.get(), target.get()));
+        assert!(target.distance_from(&hue_shifted) < target.distance_from(&lighter));
+        assert_eq!(target.quantize(&[lighter, hue_shifted]).get(), hue_shifted.get());
+    }
+
+    #[test]
+    fn quantize_hue_n_snaps_to_sectors() {
+        for (hue, expected) in [(20.0, 0.0), (40.0, 60.0), (200.0, 180.0), (340.0, 0.0)] {
+            let mut pixel = OklchPixel(0.5, 0.1, hue);
+            assert_eq!(pixel.quantize_hue_n(6, 0.0).2, expected);
+        }
+
+        let mut pixel = OklchPixel(0.5, 0.1, 20.0);
+        assert_eq!(pixel.quantize_hue_n(6, 15.0).2, 15.0);
+    }
+
+    #[test]
+    fn mix_arc_stays_vivid() {
+        let (red, green) = (colours::RED.as_oklch(), colours::GREEN.as_oklch());
+        let straight = OklabPixel::lerp(&red.as_oklab(), &green.as_oklab(), 0.5).as_oklch();
+
+        assert!(red.mix_arc(&green, 0.5).1 > straight.1);
+        assert!(red.mix_arc(&green, 1.0).distance_from(&red) < 1e-5);
+        assert!(red.mix_arc(&green, 0.0).distance_from(&green) < 1e-5);
+    }
+
+    #[test]
+    fn gamut_limit_reports_the_limiting_channel() {
+        assert_eq!(OklchPixel(0.5, 0.05, 264.0).gamut_limit(), None);
+
+        // a deep blue runs out of red before anything else
+        let deep_blue = OklchPixel(0.3, 0.35, 264.0);
+        assert!(!deep_blue.is_in_gamut(Gamut::Srgb));
+        assert_eq!(deep_blue.gamut_limit(), Some((Channel::Red, Sign::Negative)));
+
+        // a light blue runs out of blue at the top instead
+        let light_blue = OklchPixel(0.8, 0.35, 264.0);
+        assert_eq!(light_blue.gamut_limit(), Some((Channel::Blue, Sign::Positive)));
+    }
+}
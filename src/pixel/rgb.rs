@@ -1,11 +1,289 @@
-use crate::comparisons::rgb_weighted_euclidean;
+use std::ops::RangeInclusive;
 
-use super::{hsl::HslPixel, lab::LabPixel, lch::LchPixel, oklab::OklabPixel, oklch::OklchPixel};
+use crate::{comparisons::{cie76, cie94, ciede2000, delta_e_ok, rgb_weighted_euclidean}, conversions::{chain_conversions, linear_rgb_to_srgb, planckian_xy, rgb_to_xyz_d65, srgb_to_linear_rgb, uv_to_xy, von_kries_adapt, xy_to_uv, xy_to_xyz, xyz_d65_to_display_p3, xyz_d65_to_rec2020, D65_CHROMATICITY}, palette::PreparedPalette};
 
-#[derive(Debug, Clone, Copy)]
+use super::{hsl::HslPixel, lab::LabPixel, lch::LchPixel, mono::MonoPixel, oklab::OklabPixel, oklch::OklchPixel, xyz::XyzPixel, InvalidByteLength, round_decimals};
+
+#[derive(Debug, Clone, Copy, Default)]
 /// Represents a pixel in the RGB colour space. Each value (RGB) ranges between 0.0 and 1.0.
+///
+/// HDR values (above `1.0`) are allowed, and survive conversions to and from the other colour spaces.
+/// However, `clamp`, `add_error`, and the gradient builders clamp their output to `0.0` to `1.0` - use
+/// the `*_unclamped` variants where available if you need to keep them.
 pub struct RgbPixel(pub f32, pub f32, pub f32);
 
+/// Colours with less (OKLCH) chroma than this are treated as achromatic - they have no meaningful hue.
+pub(crate) const ACHROMATIC_CHROMA: f32 = 1e-4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// One of the three channels of an `RgbPixel`.
+pub enum Channel {
+    Red,
+    Green,
+    Blue,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Which side of its range a channel is out on.
+pub enum Sign {
+    /// Below `0.0`.
+    Negative,
+    /// Above `1.0`.
+    Positive,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// The range of colours that a display (or standard) can show. Wider gamuts can show more
+/// saturated colours - so a colour can be in-gamut for one, but not another.
+pub enum Gamut {
+    /// The standard RGB gamut - what `RgbPixel` is in.
+    Srgb,
+    /// The Display P3 gamut, used by most modern phones and laptops.
+    DisplayP3,
+    /// The Rec. 2020 gamut, used for UHD video.
+    Rec2020,
+}
+
+impl Gamut {
+    /// Converts an `RgbPixel` to the linear channels of the gamut's own RGB primaries - so `Gamut::Srgb` only
+    /// removes the transfer function.
+    ///
+    /// A colour is within the gamut when every channel is within `0.0` to `1.0`.
+    pub fn convert(&self, rgb: &RgbPixel) -> (f32, f32, f32) {
+        match self {
+            Gamut::Srgb => srgb_to_linear_rgb(rgb.get()),
+            Gamut::DisplayP3 => chain_conversions(rgb.get(), &[srgb_to_linear_rgb, rgb_to_xyz_d65, xyz_d65_to_display_p3]),
+            Gamut::Rec2020 => chain_conversions(rgb.get(), &[srgb_to_linear_rgb, rgb_to_xyz_d65, xyz_d65_to_rec2020]),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// The space that colours are mixed (or averaged) in - each gives a different result.
+pub enum MixSpace {
+    /// The raw channel values. Cheapest, but tends to darken mixes of bright colours.
+    Rgb,
+    /// Linear light - treating the channels as encoded sRGB (like the bytes of an image), and removing
+    /// the transfer function first. This is the physically correct average, as if the colours were blurred.
+    LinearRgb,
+    /// OKLAB, so the result is perceptually in-between.
+    Oklab,
+    /// CIELAB (relative to D50) - the older perceptual space, for workflows standardised on it.
+    CieLab,
+    /// CIELCH (relative to D50) - the hue takes the shortest way around, and greys take on the other colour's hue.
+    CieLch,
+}
+
+impl MixSpace {
+    /// Linearly interpolates between `a` and `b` in this space - where a `t` of `0.0` returns `a`, and `1.0` returns `b`.
+    pub fn lerp(&self, a: &RgbPixel, b: &RgbPixel, t: f32) -> RgbPixel {
+        match self {
+            MixSpace::Rgb => RgbPixel::lerp(a, b, t),
+            MixSpace::LinearRgb => {
+                let (a, b) = (RgbPixel::from(srgb_to_linear_rgb(a.get())), RgbPixel::from(srgb_to_linear_rgb(b.get())));
+                linear_rgb_to_srgb(RgbPixel::lerp(&a, &b, t).get()).into()
+            }
+            MixSpace::Oklab => OklabPixel::lerp(&a.as_oklab(), &b.as_oklab(), t).as_rgb(),
+            MixSpace::CieLab => LabPixel::lerp(&a.as_lab(), &b.as_lab(), t).as_rgb(),
+            MixSpace::CieLch => LchPixel::lerp(&a.as_lch(), &b.as_lch(), t).as_rgb(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Which colour to pick when quantizing, if several palette colours are exactly as near as each other.
+pub enum TieBreak {
+    /// The one that comes first in the palette - what `quantize` does.
+    First,
+    /// The one that comes last in the palette.
+    Last,
+    /// The one with the lowest relative luminance.
+    Darker,
+    /// The one with the highest relative luminance.
+    Lighter,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+/// Whether an RGB colour fits within the `0.0` to `1.0` range of each channel.
+pub enum GamutStatus {
+    InGamut,
+    /// The colour had to be clamped. `channel` is the channel that was furthest out of range, and
+    /// `amount` is how far out of range it was before clamping (always positive).
+    Clipped { channel: Channel, amount: f32 },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// One of the distance (ΔE) algorithms, for picking which one to use at runtime.
+pub enum DeltaEMethod {
+    /// Weighted euclidean distance in RGB. Fast, but not very perceptual.
+    WeightedEuclidean,
+    Cie76,
+    Cie94,
+    Ciede2000,
+    /// Euclidean distance in OKLAB.
+    Ok,
+}
+
+impl DeltaEMethod {
+    /// Converts a colour into the components this method measures distances between - like LAB for `Cie76`.
+    pub(crate) fn prepare(&self, rgb: &RgbPixel) -> (f32, f32, f32) {
+        match self {
+            DeltaEMethod::WeightedEuclidean => rgb.get(),
+            DeltaEMethod::Cie76 => rgb.as_lab().get(),
+            DeltaEMethod::Cie94 | DeltaEMethod::Ciede2000 => rgb.as_lch().get(),
+            DeltaEMethod::Ok => rgb.as_oklab().get(),
+        }
+    }
+
+    /// Measures the distance between two colours that were already converted with `prepare`.
+    pub(crate) fn distance(&self, a: (f32, f32, f32), b: (f32, f32, f32)) -> f32 {
+        match self {
+            DeltaEMethod::WeightedEuclidean => rgb_weighted_euclidean(a, b),
+            DeltaEMethod::Cie76 => cie76(a, b),
+            DeltaEMethod::Cie94 => cie94(a, b),
+            DeltaEMethod::Ciede2000 => ciede2000(a, b),
+            DeltaEMethod::Ok => delta_e_ok(a, b),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+/// The distance between two colours, as measured by each of the distance algorithms.
+pub struct ColourComparison {
+    pub weighted_euclidean: f32,
+    pub cie76: f32,
+    pub cie94: f32,
+    pub ciede2000: f32,
+    pub delta_e_ok: f32,
+}
+
+#[derive(Debug, Clone, Copy)]
+/// An error for when a conversion produces an RGB colour outside of the `0.0` to `1.0` range.
+pub struct OutOfGamut {
+    /// The raw, unclamped RGB colour.
+    pub rgb: RgbPixel,
+    /// How far the furthest channel was out of range.
+    pub max_excess: f32,
+}
+
+impl std::fmt::Display for OutOfGamut {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?} is out of the RGB gamut by {}", self.rgb, self.max_excess)
+    }
+}
+
+impl std::error::Error for OutOfGamut {}
+
+/// A 64-bit FNV-1a hasher. Unlike `DefaultHasher`, it isn't seeded randomly - so its output stays the same
+/// across runs, which `RgbPixel::from_hash` relies on.
+///
+/// Integers are hashed as little-endian bytes (and `usize`/`isize` as 64-bit), rather than in the native
+/// layout - so the output is also the same across platforms.
+struct FnvHasher(u64);
+
+impl FnvHasher {
+    fn new() -> FnvHasher {
+        FnvHasher(0xcbf29ce484222325)
+    }
+}
+
+impl std::hash::Hasher for FnvHasher {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        const PRIME: u64 = 0x100000001b3;
+        for byte in bytes {
+            self.0 ^= *byte as u64;
+            self.0 = self.0.wrapping_mul(PRIME);
+        }
+    }
+
+    fn write_u16(&mut self, value: u16) {
+        self.write(&value.to_le_bytes());
+    }
+
+    fn write_u32(&mut self, value: u32) {
+        self.write(&value.to_le_bytes());
+    }
+
+    fn write_u64(&mut self, value: u64) {
+        self.write(&value.to_le_bytes());
+    }
+
+    fn write_u128(&mut self, value: u128) {
+        self.write(&value.to_le_bytes());
+    }
+
+    fn write_usize(&mut self, value: usize) {
+        self.write_u64(value as u64);
+    }
+
+    fn write_i16(&mut self, value: i16) {
+        self.write_u16(value as u16);
+    }
+
+    fn write_i32(&mut self, value: i32) {
+        self.write_u32(value as u32);
+    }
+
+    fn write_i64(&mut self, value: i64) {
+        self.write_u64(value as u64);
+    }
+
+    fn write_i128(&mut self, value: i128) {
+        self.write_u128(value as u128);
+    }
+
+    fn write_isize(&mut self, value: isize) {
+        self.write_u64(value as i64 as u64);
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+/// Wraps an `RgbPixel` so it can be sorted from dark to light, by its OKLAB lightness.
+///
+/// Colours don't have a natural order, so `RgbPixel` itself can't be sorted - but wrapping it lets you
+/// `sort()` a `Vec<ByLightness>` directly. Colours with the same lightness are ordered by their channels,
+/// so the order is total (and `NaN`s are sorted consistently too).
+pub struct ByLightness(pub RgbPixel);
+
+impl ByLightness {
+    fn key(&self) -> [f32; 4] {
+        let RgbPixel(r, g, b) = self.0;
+        [self.0.as_oklab().0, r, g, b]
+    }
+}
+
+impl PartialEq for ByLightness {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == std::cmp::Ordering::Equal
+    }
+}
+
+impl Eq for ByLightness {}
+
+impl PartialOrd for ByLightness {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for ByLightness {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.key()
+            .iter()
+            .zip(other.key())
+            .map(|(a, b)| a.total_cmp(&b))
+            .find(|ordering| ordering.is_ne())
+            .unwrap_or(std::cmp::Ordering::Equal)
+    }
+}
+
+/// The tones used by Material Design's tonal palettes - see `RgbPixel::tonal_palette`.
+pub const MATERIAL_TONES: [u8; 11] = [0, 10, 20, 30, 40, 50, 60, 70, 80, 90, 100];
+
 pub mod colours {
     use super::RgbPixel;
 
@@ -45,12 +323,59 @@ impl From<(u8, u8, u8)> for RgbPixel {
     }
 }
 
+/// Reads exactly 3 bytes - red, green, and blue.
+impl TryFrom<&[u8]> for RgbPixel {
+    type Error = InvalidByteLength;
+
+    fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
+        match *value {
+            [r, g, b] => Ok((r, g, b).into()),
+            _ => Err(InvalidByteLength { expected: 3, found: value.len() }),
+        }
+    }
+}
+
+/// Each channel is clamped to `0~255` first - so negative values become `0.0` rather than wrapping around.
+impl From<(i32, i32, i32)> for RgbPixel {
+    fn from(value: (i32, i32, i32)) -> Self {
+        let channel = |value: i32| value.clamp(0, 255) as f32 / 255.0;
+        RgbPixel(channel(value.0), channel(value.1), channel(value.2))
+    }
+}
+
 impl From<(f32, f32, f32)> for RgbPixel {
     fn from(value: (f32, f32, f32)) -> Self {
         RgbPixel(value.0, value.1, value.2)
     }
 }
 
+/// Each component is rounded to the nearest `f32` - any extra precision is lost here, rather than
+/// before the pixel is constructed.
+impl From<(f64, f64, f64)> for RgbPixel {
+    fn from(value: (f64, f64, f64)) -> Self {
+        RgbPixel(value.0 as f32, value.1 as f32, value.2 as f32)
+    }
+}
+
+/// The complement of each channel (`1.0 - channel`) - inverting the colour. Channels outside of
+/// `0.0` to `1.0` aren't clamped, so the complement of `1.2` is `-0.2`.
+impl std::ops::Neg for RgbPixel {
+    type Output = RgbPixel;
+
+    fn neg(self) -> Self::Output {
+        RgbPixel(1.0 - self.0, 1.0 - self.1, 1.0 - self.2)
+    }
+}
+
+/// The same as `Neg` - so `!pixel` can be read as "not this colour".
+impl std::ops::Not for RgbPixel {
+    type Output = RgbPixel;
+
+    fn not(self) -> Self::Output {
+        -self
+    }
+}
+
 impl From<&str> for RgbPixel {
     fn from(value: &str) -> Self {
         let r = u8::from_str_radix(&value[0..=1], 16);
@@ -74,11 +399,75 @@ impl From<&str> for RgbPixel {
 }
 
 impl RgbPixel {
+    /// The valid range of the red channel.
+    pub const R_RANGE: RangeInclusive<f32> = 0.0..=1.0;
+    /// The valid range of the green channel.
+    pub const G_RANGE: RangeInclusive<f32> = 0.0..=1.0;
+    /// The valid range of the blue channel.
+    pub const B_RANGE: RangeInclusive<f32> = 0.0..=1.0;
+
+    /// Deterministically picks a colour for any hashable value - useful for tagging things like IDs in graphs.
+    ///
+    /// The same value always maps to the same colour - across runs and platforms. The hue is derived from the hash,
+    /// while the lightness and chroma are fixed (in OKLCH) - so every colour is similarly vivid.
+    ///
+    /// The colour depends on how the value's `Hash` implementation feeds it to the hasher. That's stable for your own
+    /// types - but the standard library doesn't promise it for its types (like `str`, which adds a terminator byte),
+    /// so their colours could change between Rust versions.
+    pub fn from_hash<H: std::hash::Hash + ?Sized>(value: &H) -> RgbPixel {
+        use std::hash::Hasher;
+
+        const LIGHTNESS: f32 = 0.7;
+        const CHROMA: f32 = 0.15;
+
+        let mut hasher = FnvHasher::new();
+        value.hash(&mut hasher);
+        let hue = (hasher.finish() % 3600) as f32 / 10.0;
+
+        OklchPixel(LIGHTNESS, CHROMA, hue).gamut_map(Gamut::Srgb)
+    }
+
     /// Retrieves the (r, g, b) channels of the pixel as a tuple.
     pub fn get(&self) -> (f32, f32, f32) {
         (self.0, self.1, self.2)
     }
 
+    /// Rounds each channel to `decimals` decimal places - so results that differ in the last few bits
+    /// between platforms (or compilers) compare and print the same.
+    ///
+    /// This is meant for snapshot tests and display - it doesn't make the pixel any more precise,
+    /// and throws away detail that later conversions could have used.
+    #[must_use]
+    pub fn round_to(&self, decimals: u32) -> Self {
+        RgbPixel(round_decimals(self.0, decimals), round_decimals(self.1, decimals), round_decimals(self.2, decimals))
+    }
+
+    /// Linearly interpolates between `a` and `b` - where a `t` of `0.0` returns `a`, and `1.0` returns `b`.
+    ///
+    /// Unlike `mix`, both endpoints are explicit and `t` isn't clamped - so easing functions that
+    /// overshoot will extrapolate past either end.
+    pub fn lerp(a: &RgbPixel, b: &RgbPixel, t: f32) -> RgbPixel {
+        let lerp = |start: f32, end: f32| start + (end - start) * t;
+        RgbPixel(lerp(a.0, b.0), lerp(a.1, b.1), lerp(a.2, b.2))
+    }
+
+    /// Samples evenly-spaced colours at `t` (from `0.0` to `1.0`) - like a multi-stop gradient, without building a `Gradient`.
+    ///
+    /// `t` is clamped, and landing exactly on a stop returns its colour as-is. An empty list returns black.
+    pub fn mix_array(colours: &[RgbPixel], t: f32, space: MixSpace) -> RgbPixel {
+        let Some(last) = colours.len().checked_sub(1) else {
+            return colours::BLACK;
+        };
+
+        let position = t.clamp(0.0, 1.0) * last as f32;
+        if position.fract() == 0.0 {
+            return colours[position as usize];
+        }
+
+        let index = (position as usize).min(last - 1);
+        space.lerp(&colours[index], &colours[index + 1], position - index as f32)
+    }
+
     pub fn get_u8(&self) -> (u8, u8, u8) {
         (
             (self.0 * 255.0).round() as u8,
@@ -87,37 +476,254 @@ impl RgbPixel {
         )
     }
 
+    /// Writes the pixel as 3 bytes (r, g, b) to the start of `out`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `out` is shorter than 3 bytes.
+    pub fn write_rgb8(&self, out: &mut [u8]) {
+        let (r, g, b) = self.get_u8();
+        out[..3].copy_from_slice(&[r, g, b]);
+    }
+
+    /// Reads a pixel from the first 3 bytes (r, g, b) of `bytes`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bytes` is shorter than 3 bytes.
+    pub fn read_rgb8(bytes: &[u8]) -> RgbPixel {
+        (bytes[0], bytes[1], bytes[2]).into()
+    }
+
+    /// Reads a packed buffer of RGB bytes into pixels.
+    ///
+    /// Errors if the length isn't a multiple of 3 - where `expected` is the length of the whole pixels.
+    pub fn from_rgb8_slice(bytes: &[u8]) -> Result<Vec<RgbPixel>, InvalidByteLength> {
+        match bytes.len() % 3 {
+            0 => Ok(bytes.chunks_exact(3).map(Self::read_rgb8).collect()),
+            leftover => Err(InvalidByteLength { expected: bytes.len() - leftover, found: bytes.len() }),
+        }
+    }
+
+    /// Packs a list of pixels into a buffer of RGB bytes - 3 bytes per pixel.
+    pub fn to_rgb8_vec(pixels: &[RgbPixel]) -> Vec<u8> {
+        let mut bytes = vec![0; pixels.len() * 3];
+        for (pixel, out) in pixels.iter().zip(bytes.chunks_exact_mut(3)) {
+            pixel.write_rgb8(out);
+        }
+        bytes
+    }
+
+    /// Clamps each channel to `0.0` to `1.0`. This will crush any HDR (`> 1.0`) values.
+    #[must_use]
     pub fn clamp(&self) -> RgbPixel {
         (
-            self.0.clamp(0.0, 1.0),
-            self.1.clamp(0.0, 1.0),
-            self.2.clamp(0.0, 1.0),
+            self.0.clamp(*Self::R_RANGE.start(), *Self::R_RANGE.end()),
+            self.1.clamp(*Self::G_RANGE.start(), *Self::G_RANGE.end()),
+            self.2.clamp(*Self::B_RANGE.start(), *Self::B_RANGE.end()),
         ).into()
     }
 
-    /// Adds an error to each of the channels.
+    /// Brings HDR (`> 1.0`) values back within range by dividing every channel by the brightest one - an
+    /// alternative to `clamp` that keeps the ratios between the channels (and so the hue), rather than
+    /// blowing highlights out towards white.
+    ///
+    /// Pixels whose brightest channel is already at most `1.0` are returned as-is. Negative channels aren't touched.
+    #[must_use]
+    pub fn scale_to_fit_gamut(&self) -> RgbPixel {
+        let max = self.0.max(self.1).max(self.2);
+        if max <= *Self::R_RANGE.end() {
+            return *self;
+        }
+
+        RgbPixel(self.0 / max, self.1 / max, self.2 / max)
+    }
+
+    /// Retrieves the lower of each channel between two pixels - like the "darken" blend mode.
+    #[must_use]
+    pub fn min_channels(&self, other: &RgbPixel) -> RgbPixel {
+        RgbPixel(self.0.min(other.0), self.1.min(other.1), self.2.min(other.2))
+    }
+
+    /// Retrieves the higher of each channel between two pixels - like the "lighten" blend mode.
+    #[must_use]
+    pub fn max_channels(&self, other: &RgbPixel) -> RgbPixel {
+        RgbPixel(self.0.max(other.0), self.1.max(other.1), self.2.max(other.2))
+    }
+
+    /// Clamps each channel between the channels of `lo` and `hi`.
+    ///
+    /// Unlike `f32::clamp`, this doesn't panic if `lo` is above `hi` for a channel - `hi` wins instead.
+    #[must_use]
+    pub fn clamp_channels(&self, lo: &RgbPixel, hi: &RgbPixel) -> RgbPixel {
+        self.max_channels(lo).min_channels(hi)
+    }
+
+    /// Checks whether the pixel can be shown within a gamut. For `Gamut::Srgb` this checks that each
+    /// channel is within `0.0` to `1.0` - other gamuts check the channels after converting to them.
+    pub fn is_in_gamut(&self, gamut: Gamut) -> bool {
+        const EPSILON: f32 = 1e-5;
+
+        match gamut {
+            Gamut::Srgb => self.gamut_status() == GamutStatus::InGamut,
+            _ => {
+                let (r, g, b) = gamut.convert(self);
+                [r, g, b].iter().all(|channel| (-EPSILON..=1.0 + EPSILON).contains(channel))
+            }
+        }
+    }
+
+    /// Reports whether the pixel is within `0.0` to `1.0` - and if not, which channel is the
+    /// furthest out and by how much.
+    pub fn gamut_status(&self) -> GamutStatus {
+        let excess = |value: f32| if value > 1.0 {
+            value - 1.0
+        } else if value < 0.0 {
+            -value
+        } else {
+            0.0
+        };
+
+        let (channel, amount) = [
+            (Channel::Red, excess(self.0)),
+            (Channel::Green, excess(self.1)),
+            (Channel::Blue, excess(self.2)),
+        ]
+            .into_iter()
+            .fold((Channel::Red, 0.0), |worst, current| if current.1 > worst.1 { current } else { worst });
+
+        if amount > 0.0 {
+            GamutStatus::Clipped { channel, amount }
+        } else {
+            GamutStatus::InGamut
+        }
+    }
+
+    /// Adds an error to each of the channels. The result is clamped to `0.0` to `1.0`.
+    #[must_use]
     pub fn add_error(self, error: (f32, f32, f32)) -> RgbPixel {
+        self.add_error_unclamped(error).clamp()
+    }
+
+    /// Adds an error to each of the channels *without* clamping - so HDR values survive.
+    #[must_use]
+    pub fn add_error_unclamped(self, error: (f32, f32, f32)) -> RgbPixel {
         RgbPixel(
-            (self.0 + error.0).min(1.0).max(0.0),
-            (self.1 + error.1).min(1.0).max(0.0),
-            (self.2 + error.2).min(1.0).max(0.0),
+            self.0 + error.0,
+            self.1 + error.1,
+            self.2 + error.2,
         )
     }
 
     /// Quantizes the RGB pixel to the nearest colour in the palette.
     pub fn quantize(&self, palette: &[RgbPixel]) -> RgbPixel {
+        match self.quantize_index(palette) {
+            Some(index) => palette[index],
+            None => *self,
+        }
+    }
+
+    /// Like `quantize`, but with an explicit policy for when several colours are exactly as near as each other -
+    /// so the result doesn't depend on the order of the palette. `quantize` is the same as `TieBreak::First`.
+    pub fn quantize_with_tiebreak(&self, palette: &[RgbPixel], policy: TieBreak) -> RgbPixel {
+        let distances: Vec<f32> = palette.iter().map(|colour| colour.distance_from(self)).collect();
+        let closest_distance = distances.iter().copied().fold(f32::MAX, f32::min);
+        let mut tied = palette.iter().zip(&distances).filter(|(_, distance)| **distance == closest_distance).map(|(colour, _)| colour);
+
+        let by_luminance = |a: &&RgbPixel, b: &&RgbPixel| a.relative_luminance().total_cmp(&b.relative_luminance());
+        let chosen = match policy {
+            TieBreak::First => tied.next(),
+            TieBreak::Last => tied.next_back(),
+            // `min_by` keeps the first of equal elements, and `max_by` the last
+            TieBreak::Darker => tied.min_by(by_luminance),
+            TieBreak::Lighter => tied.max_by(by_luminance),
+        };
+
+        chosen.copied().unwrap_or(*self)
+    }
+
+    /// Retrieves the index of the nearest colour in the palette - or `None` if the palette is empty.
+    ///
+    /// If multiple colours are equally near, the first one is picked.
+    pub fn quantize_index(&self, palette: &[RgbPixel]) -> Option<usize> {
+        let mut closest_distance = f32::MAX;
+        let mut current_index = None;
+
+        for (index, colour) in palette.iter().enumerate() {
+            let distance = colour.distance_from(self);
+            if distance < closest_distance {
+                current_index = Some(index);
+                closest_distance = distance;
+            };
+        }
+
+        current_index
+    }
+
+    /// Like `quantize_index`, but only considers the palette entries where `allowed` is `true` - for palettes
+    /// with locked (reserved) slots. Entries past the end of `allowed` aren't allowed.
+    ///
+    /// Retrieves `None` if no entry is allowed.
+    pub fn quantize_index_masked(&self, palette: &[RgbPixel], allowed: &[bool]) -> Option<usize> {
         let mut closest_distance = f32::MAX;
-        let mut current_colour = self;
+        let mut current_index = None;
 
-        for colour in palette.iter() {
+        for (index, (colour, _)) in palette.iter().zip(allowed).enumerate().filter(|(_, (_, allowed))| **allowed) {
             let distance = colour.distance_from(self);
             if distance < closest_distance {
-                current_colour = colour;
+                current_index = Some(index);
                 closest_distance = distance;
             };
         }
 
-        current_colour.get().into()
+        current_index
+    }
+
+    /// Quantizes the pixel to the colour in the palette that's nearest by `metric` - converting the pixel and
+    /// each palette entry into the metric's space as it goes, so nothing else needs converting up front.
+    ///
+    /// Every call converts the whole palette - when quantizing many pixels against one palette, build a
+    /// `PreparedPalette` once and use `PreparedPalette::quantize` instead.
+    pub fn quantize_perceptual(&self, palette: &[RgbPixel], metric: DeltaEMethod) -> RgbPixel {
+        PreparedPalette::new(palette, metric).quantize(self)
+    }
+
+    /// Quantizes the pixel by blending the two nearest colours in the palette, weighted by how near each one is.
+    ///
+    /// `sharpness` controls how hard the transition between colours is - each weight is the inverse of
+    /// the distance raised to `sharpness`. Higher values approach `quantize`, while `0.0` always mixes equally.
+    /// This helps avoid the banding that hard quantization produces.
+    pub fn quantize_soft(&self, palette: &[RgbPixel], sharpness: f32) -> RgbPixel {
+        let ranking = self.rank_palette(palette);
+
+        match ranking[..] {
+            [] => *self,
+            [(nearest, _)] => palette[nearest],
+            [(nearest, nearest_distance), (second, second_distance), ..] => {
+                if nearest_distance == 0.0 {
+                    return palette[nearest];
+                }
+
+                // the same as `d1^-s / (d1^-s + d2^-s)` - but since `d1 <= d2`, the power can't overflow at high sharpness
+                let ratio = 1.0 / (1.0 + (nearest_distance / second_distance).powf(sharpness));
+
+                palette[nearest].mix(ratio, &palette[second])
+            }
+        }
+    }
+
+    /// Ranks every colour in the palette by its distance from the pixel, nearest first.
+    ///
+    /// Returns `(index, distance)` pairs - useful for figuring out *why* `quantize` picked a colour.
+    pub fn rank_palette(&self, palette: &[RgbPixel]) -> Vec<(usize, f32)> {
+        let mut ranking: Vec<(usize, f32)> = palette
+            .iter()
+            .enumerate()
+            .map(|(index, colour)| (index, colour.distance_from(self)))
+            .collect();
+
+        ranking.sort_by(|a, b| a.1.total_cmp(&b.1));
+        ranking
     }
 
     /// Mixes two colours together to produce a third colour.
@@ -134,8 +740,15 @@ impl RgbPixel {
     /// RED.mix(&BLUE, 0.0) = BLUE
     /// RED.mix(&BLUE, 1.0) = RED
     /// ```
+    ///
+    /// The ratio is clamped to `0.0` to `1.0`, but the channels themselves aren't - so HDR values are kept.
     pub fn mix(&self, ratio: f32, other: &RgbPixel) -> Self {
-        let ratio = ratio.clamp(0.0, 1.0);
+        self.mix_unclamped(ratio.clamp(0.0, 1.0), other)
+    }
+
+    /// Same as `mix`, except the ratio isn't clamped either. Passing a ratio outside of
+    /// `0.0` to `1.0` will extrapolate past either colour.
+    pub fn mix_unclamped(&self, ratio: f32, other: &RgbPixel) -> Self {
         let mix_calc = |pixchan1: f32, pixchan2: f32| {
             (pixchan1 * ratio) + pixchan2 * (1.0 - ratio)
         };
@@ -146,55 +759,365 @@ impl RgbPixel {
         )
     }
 
-    /// This function will generate a list of colours with the same hue but
-    /// varying brightness - by using the HSL colour space.
+    /// Gets the colour halfway between itself and another colour, as perceived by the eye.
     ///
-    /// `shades` determines how many shades get generated. Passing `1` will
-    /// return a vector with a single colour containing `0.5` luminance - for example.
+    /// Unlike `mix(0.5, ..)` - which averages in RGB - this averages in OKLAB. As an example, the
+    /// midpoint of black and white is a grey with an OKLAB lightness of `0.5`.
+    pub fn perceptual_midpoint(&self, other: &RgbPixel) -> RgbPixel {
+        Self::perceptual_mean(&[*self, *other])
+    }
+
+    /// Averages a list of colours in OKLAB, which gives a perceptually even blend of all of them.
     ///
-    /// **Note:** This will *not* include black and white.
-    pub fn build_gradient_using_hsl(&self, shades: u16) -> Vec<Self> {
-        let fractional = 1.0 / (shades + 1) as f32;
-        (1..=shades)
-            .into_iter()
-            .map(|i| {
-                self.as_hsl()
-                    // set the luminance to black first
-                    .add_luminance(-2.0)
-                    .add_luminance(i as f32 * fractional)
-                    .as_rgb()
-                    .clamp()
-            })
-            .collect()
+    /// Returns black if the list is empty.
+    pub fn perceptual_mean(colours: &[RgbPixel]) -> RgbPixel {
+        if colours.is_empty() {
+            return colours::BLACK;
+        }
+
+        let count = colours.len() as f32;
+        let (l, a, b) = colours
+            .iter()
+            .map(|colour| colour.as_oklab().get())
+            .fold((0.0, 0.0, 0.0), |acc, lab| (acc.0 + lab.0, acc.1 + lab.1, acc.2 + lab.2));
+
+        OklabPixel(l / count, a / count, b / count).as_rgb()
     }
 
-    /// This function will generate a list of colours with the same hue but
-    /// varying brightness - by using the OKLCH colour space.
+    /// Paints the pixel on top of an (opaque) background with the given opacity, giving an opaque result.
+    /// An `alpha` of `1.0` is the pixel itself, and `0.0` is the background. The alpha is clamped between the two.
     ///
-    /// `shades` determines how many shades get generated. Passing `1` will
-    /// return a vector with a single colour containing `0.5` luminance - for example.
+    /// The blend is done in linear light - treating the channels as encoded sRGB, like `MixSpace::LinearRgb` - so
+    /// it looks like a real semi-transparent layer, rather than darkening in the middle.
+    pub fn blend_over(&self, background: &RgbPixel, alpha: f32) -> RgbPixel {
+        let alpha = alpha.clamp(0.0, 1.0);
+        let (foreground, background) = (srgb_to_linear_rgb(self.get()), srgb_to_linear_rgb(background.get()));
+        let blend = |top: f32, bottom: f32| alpha * top + (1.0 - alpha) * bottom;
+
+        linear_rgb_to_srgb((
+            blend(foreground.0, background.0),
+            blend(foreground.1, background.1),
+            blend(foreground.2, background.2),
+        )).into()
+    }
+
+    /// Adjusts the exposure by a number of stops - each stop doubles (or halves) the light, like a camera.
     ///
-    /// **Note:** This will *not* include black and white.
-    pub fn build_gradient_using_oklch(&self, shades: u16) -> Vec<Self> {
-        let fractional = 1.0 / (shades + 1) as f32;
-        (1..=shades)
-            .into_iter()
-            .map(|i| {
-                self.as_oklch()
-                    // set the luminance to black first
-                    .add_luma(-2.0)
-                    .add_luma(i as f32 * fractional)
-                    .as_rgb()
-                    .clamp()
-            })
-            .collect()
+    /// The channels are scaled in linear light - treating them as encoded sRGB, like `MixSpace::LinearRgb` - so
+    /// the hue and colour temperature stay the same. The result isn't clamped, so HDR values are kept - use
+    /// `clamp` afterwards if you need them within `0.0` to `1.0`.
+    pub fn adjust_exposure(&self, stops: f32) -> RgbPixel {
+        let (r, g, b) = srgb_to_linear_rgb(self.get());
+        let factor = stops.exp2();
+
+        linear_rgb_to_srgb((r * factor, g * factor, b * factor)).into()
     }
 
-    /// This function will build a gradient by mixing the current colour with another
-    /// using various ratios.
+    /// Scales the saturation (OKLCH chroma) by `factor`, rolling off softly towards the edge of the sRGB gamut -
+    /// so boosting already-saturated colours compresses them against the edge, rather than clipping.
     ///
-    /// `mixes` determines how many mixes get generated. Passing `1` will return
-    /// a vector with a single colour that mixes both equally.
+    /// Small boosts are close to linear, while the result never passes the most saturated colour at the same
+    /// lightness and hue. Reducing the saturation (a `factor` below `1.0`) is exactly linear, and a `factor`
+    /// of `1.0` leaves the colour as-is. Greys have no hue to saturate, so they're returned as-is.
+    #[must_use]
+    pub fn adjust_saturation_soft(&self, factor: f32) -> RgbPixel {
+        let (lightness, chroma, hue) = self.as_oklch().get();
+        if hue.is_nan() || chroma < ACHROMATIC_CHROMA || factor == 1.0 {
+            return *self;
+        }
+
+        let max_chroma = OklchPixel(lightness, *OklchPixel::C_RANGE.end(), hue).gamut_map(Gamut::Srgb).as_oklch().1;
+        let chroma = chroma.min(max_chroma);
+        let headroom = max_chroma - chroma;
+        let increase = chroma * factor.max(0.0) - chroma;
+
+        // `tanh` is linear for small increases, and approaches the edge of the gamut for large ones
+        let adjusted = if increase <= 0.0 || headroom <= 0.0 {
+            chroma + increase.min(0.0)
+        } else {
+            chroma + headroom * (increase / headroom).tanh()
+        };
+
+        OklchPixel(lightness, adjusted, hue).gamut_map(Gamut::Srgb)
+    }
+
+    /// Mixes the hue and chroma of two colours in OKLCH, while setting the lightness to `target_l`.
+    /// The ratio works the same way as in `mix`.
+    ///
+    /// If `target_l` is `None`, the lightness is interpolated like the other components. The result is
+    /// gamut-mapped, so the lightness is kept even if some chroma has to be dropped. This is useful for
+    /// building iso-luminant palettes.
+    pub fn mix_preserve_lightness(&self, other: &RgbPixel, ratio: f32, target_l: Option<f32>) -> RgbPixel {
+        let mut mixed = self.as_oklch().mix(ratio, &other.as_oklch());
+        if let Some(lightness) = target_l {
+            mixed.0 = lightness;
+        }
+        mixed.gamut_map(Gamut::Srgb)
+    }
+
+    /// Retrieves the position of the pixel on a colour wheel (at its own OKLCH lightness) - for colour picker widgets.
+    ///
+    /// The angle is the hue in radians, and the radius is the chroma as a fraction of `OklchPixel::C_RANGE` -
+    /// so `0.0` is the centre (grey), and `1.0` is the edge. Greys have an angle of `0.0`.
+    pub fn to_wheel(&self) -> (f32, f32) {
+        let (_, chroma, hue) = self.as_oklch().get();
+        let angle = if hue.is_nan() { 0.0 } else { hue.to_radians() };
+
+        (angle, chroma / *OklchPixel::C_RANGE.end())
+    }
+
+    /// Retrieves the colour at a position on a colour wheel (see `to_wheel`), at the given OKLCH lightness.
+    ///
+    /// The edge of the wheel is outside of the sRGB gamut for most hues, so the result is gamut-mapped.
+    pub fn from_wheel(angle: f32, radius: f32, lightness: f32) -> RgbPixel {
+        let hue = angle.to_degrees().rem_euclid(360.0);
+        OklchPixel(lightness, radius * *OklchPixel::C_RANGE.end(), hue).gamut_map(Gamut::Srgb)
+    }
+
+    /// Tints the pixel with the hue and chroma of another colour, while keeping its own lightness (in OKLAB).
+    ///
+    /// The result is gamut-mapped, so the lightness is kept even if the tint can't be fully applied.
+    pub fn colorize(&self, tint: &RgbPixel) -> RgbPixel {
+        let (_, chroma, hue) = tint.as_oklch().get();
+        OklchPixel(self.as_oklab().0, chroma, hue).gamut_map(Gamut::Srgb)
+    }
+
+    /// Adjusts the white balance of the pixel, by adapting it (von Kries) from D65 to a shifted white point.
+    ///
+    /// - `temperature` is in mireds, and moves the white along the Planckian locus. Positive values
+    ///   warm the colour (towards amber), negative values cool it (towards blue). `100.0` is a strong shift.
+    /// - `tint` is in Δuv, and moves the white perpendicular to the locus. Positive values shift towards
+    ///   magenta, negative values towards green. `0.01` is a strong shift.
+    ///
+    /// A `temperature` and `tint` of `0.0` leaves the pixel unchanged. The result isn't clamped.
+    pub fn white_balance(&self, temperature: f32, tint: f32) -> RgbPixel {
+        const D65_CCT: f32 = 6504.0;
+
+        // the lowest mired value the locus approximation supports is 40 (25000K)
+        let target_cct = 1e6 / (1e6 / D65_CCT + temperature).max(40.0);
+
+        // shift D65 by however far the locus moves, so that no shift is an exact identity
+        let (locus_u, locus_v) = xy_to_uv(planckian_xy(target_cct));
+        let (d65_locus_u, d65_locus_v) = xy_to_uv(planckian_xy(D65_CCT));
+        let (d65_u, d65_v) = xy_to_uv(D65_CHROMATICITY);
+
+        // the normal to the locus, pointing below it (towards magenta)
+        let (next_u, next_v) = xy_to_uv(planckian_xy(target_cct * 1.01));
+        let (tangent_u, tangent_v) = (next_u - locus_u, next_v - locus_v);
+        let length = tangent_u.hypot(tangent_v);
+        let (mut normal_u, mut normal_v) = (-tangent_v / length, tangent_u / length);
+        if normal_v > 0.0 {
+            (normal_u, normal_v) = (-normal_u, -normal_v);
+        }
+
+        let target_xy = uv_to_xy((
+            d65_u + (locus_u - d65_locus_u) + tint * normal_u,
+            d65_v + (locus_v - d65_locus_v) + tint * normal_v,
+        ));
+
+        let source_white = xy_to_xyz(D65_CHROMATICITY);
+        let target_white = xy_to_xyz(target_xy);
+
+        XyzPixel::from(von_kries_adapt(XyzPixel::from_rgb(self).get(), source_white, target_white)).as_rgb()
+    }
+
+    /// Converts the pixel to greyscale, while keeping colours of the same lightness (but different hues) apart.
+    ///
+    /// The OKLAB lightness is nudged by the chroma - lighter for warm hues, darker for cool ones - with
+    /// `amount` controlling how far. An `amount` of `0.0` is a plain lightness-based greyscale, and
+    /// greys (which have no chroma) are never nudged. Around `0.5` keeps most colours distinguishable.
+    pub fn grayscale_decolorize(&self, amount: f32) -> MonoPixel {
+        const WARMEST_HUE: f32 = 45.0;
+
+        let (lightness, chroma, hue) = self.as_oklch().get();
+        let nudge = if hue.is_nan() {
+            0.0
+        } else {
+            amount * chroma * (hue - WARMEST_HUE).to_radians().cos()
+        };
+
+        // a grey's luminance is the cube of its OKLAB lightness
+        let grey = (lightness + nudge).clamp(0.0, 1.0).powi(3);
+        MonoPixel::from((grey * 255.0).round() as u8)
+    }
+
+    /// Retrieves a single channel as a greyscale pixel - scaled to `0~255`, and clamped if out of range.
+    pub fn channel_as_mono(&self, ch: Channel) -> MonoPixel {
+        let value = match ch {
+            Channel::Red => self.0,
+            Channel::Green => self.1,
+            Channel::Blue => self.2,
+        };
+
+        MonoPixel::from((value.clamp(0.0, 1.0) * 255.0).round() as u8)
+    }
+
+    /// Splits the pixel into a greyscale pixel per channel, in red, green, blue order. See `channel_as_mono`.
+    pub fn split_channels(&self) -> [MonoPixel; 3] {
+        [Channel::Red, Channel::Green, Channel::Blue].map(|ch| self.channel_as_mono(ch))
+    }
+
+    /// Rotates the hue of the pixel (in OKLCH) in `steps` even steps - for spinning-rainbow effects.
+    ///
+    /// The first colour is the pixel itself, and the next colour after the last would bring it back round
+    /// to the start. Each rotated colour is gamut-mapped. Achromatic colours (greys) have no hue to rotate,
+    /// so they're repeated as-is.
+    pub fn hue_cycle(&self, steps: usize) -> impl Iterator<Item = RgbPixel> {
+        let start = *self;
+        let oklch = self.as_oklch();
+        let achromatic = oklch.2.is_nan() || oklch.1 < ACHROMATIC_CHROMA;
+        let step = 360.0 / steps as f32;
+
+        (0..steps).map(move |i| {
+            if achromatic || i == 0 {
+                start
+            } else {
+                oklch.adjusted_hue(i as f32 * step).gamut_map(Gamut::Srgb)
+            }
+        })
+    }
+
+    /// Rotates the hue of every pixel (in OKLCH) by `degrees`, in place - a hue-shift filter for a whole image.
+    ///
+    /// Each pixel keeps its lightness and chroma, although rotated colours are gamut-mapped. Achromatic
+    /// pixels (greys) have no hue to rotate, so they're left untouched.
+    pub fn rotate_hue_batch(pixels: &mut [RgbPixel], degrees: f32) {
+        for pixel in pixels.iter_mut() {
+            let oklch = pixel.as_oklch();
+            if oklch.2.is_nan() || oklch.1 < ACHROMATIC_CHROMA {
+                continue;
+            }
+
+            *pixel = oklch.adjusted_hue(degrees).gamut_map(Gamut::Srgb);
+        }
+    }
+
+    /// Snaps the pixel to the nearest colour on the Planckian (black body) locus, keeping its luminance.
+    /// Returns the snapped colour, and its correlated colour temperature (CCT) in kelvin.
+    ///
+    /// The nearest point is the one with the smallest Δuv (in CIE 1960 UCS) - which removes any green or
+    /// magenta tint from a near-white. The CCT is limited to 1667K~25000K. Black has no chromaticity,
+    /// so it stays black (with a CCT of `NaN`).
+    pub fn snap_to_planckian(&self) -> (RgbPixel, f32) {
+        const COARSE_STEPS: usize = 64;
+        const ITERATIONS: usize = 32;
+        // the locus approximation is valid between 1667K and 25000K
+        const MIRED_RANGE: (f32, f32) = (1e6 / 25000.0, 1e6 / 1667.0);
+
+        let xyz = XyzPixel::from_rgb(self);
+        let (x, y) = xyz.chromaticity();
+        if x.is_nan() || y.is_nan() {
+            return (*self, f32::NAN);
+        }
+
+        let target = xy_to_uv((x, y));
+        let distance = |mired: f32| {
+            let (u, v) = xy_to_uv(planckian_xy(1e6 / mired));
+            (u - target.0).hypot(v - target.1)
+        };
+
+        // the locus curves, so find roughly where it's closest before narrowing in
+        let step = (MIRED_RANGE.1 - MIRED_RANGE.0) / COARSE_STEPS as f32;
+        let closest = (0..=COARSE_STEPS)
+            .map(|i| MIRED_RANGE.0 + i as f32 * step)
+            .min_by(|a, b| distance(*a).total_cmp(&distance(*b)))
+            .unwrap_or(MIRED_RANGE.0);
+
+        let (mut low, mut high) = ((closest - step).max(MIRED_RANGE.0), (closest + step).min(MIRED_RANGE.1));
+        for _ in 0..ITERATIONS {
+            let (a, b) = (low + (high - low) / 3.0, high - (high - low) / 3.0);
+            if distance(a) < distance(b) {
+                high = b;
+            } else {
+                low = a;
+            }
+        }
+
+        let cct = 1e6 / ((low + high) / 2.0);
+        let (white_x, white_y, white_z) = xy_to_xyz(planckian_xy(cct));
+        let luminance = xyz.1;
+        let snapped = XyzPixel(white_x * luminance, white_y * luminance, white_z * luminance).as_rgb();
+
+        (snapped, cct)
+    }
+
+    /// This function will generate a list of colours with the same hue but
+    /// varying brightness - by using the HSL colour space.
+    ///
+    /// `shades` determines how many shades get generated. Passing `1` will
+    /// return a vector with a single colour containing `0.5` luminance - for example.
+    ///
+    /// **Note:** This will *not* include black and white. The resulting colours are clamped.
+    pub fn build_gradient_using_hsl(&self, shades: u16) -> Vec<Self> {
+        let fractional = 1.0 / (shades + 1) as f32;
+        (1..=shades)
+            .into_iter()
+            .map(|i| {
+                self.as_hsl()
+                    // set the luminance to black first
+                    .add_luminance(-2.0)
+                    .add_luminance(i as f32 * fractional)
+                    .as_rgb()
+                    .clamp()
+            })
+            .collect()
+    }
+
+    /// This function will generate a list of colours with the same hue but
+    /// varying brightness - by using the OKLCH colour space.
+    ///
+    /// `shades` determines how many shades get generated. Passing `1` will
+    /// return a vector with a single colour containing `0.5` luminance - for example.
+    ///
+    /// **Note:** This will *not* include black and white. The resulting colours are clamped.
+    pub fn build_gradient_using_oklch(&self, shades: u16) -> Vec<Self> {
+        let fractional = 1.0 / (shades + 1) as f32;
+        (1..=shades)
+            .into_iter()
+            .map(|i| {
+                self.as_oklch()
+                    // set the luminance to black first
+                    .add_luma(-2.0)
+                    .add_luma(i as f32 * fractional)
+                    .as_rgb()
+                    .clamp()
+            })
+            .collect()
+    }
+
+    /// This function will generate a list of colours with the same hue but varying brightness, where
+    /// each colour is as saturated as it can be at that brightness - by using the OKLCH colour space.
+    ///
+    /// Unlike `build_gradient_using_oklch`, the chroma isn't kept from the original colour, so dark
+    /// and light shades don't wash out as quickly.
+    ///
+    /// **Note:** This will *not* include black and white.
+    pub fn build_tonal_ramp(&self, shades: u16) -> Vec<Self> {
+        let fractional = 1.0 / (shades + 1) as f32;
+        let hue = self.as_oklch().2;
+        (1..=shades)
+            .map(|i| OklchPixel(i as f32 * fractional, *OklchPixel::C_RANGE.end(), hue).gamut_map(Gamut::Srgb))
+            .collect()
+    }
+
+    /// Generates a tonal palette (as used by Material Design) - the colour's hue and chroma at
+    /// each of the given tones, gamut-mapped.
+    ///
+    /// Each tone ranges from `0` (black) to `100` (white), and is used as the OKLCH lightness.
+    /// Use `MATERIAL_TONES` for Material's default set of tones.
+    pub fn tonal_palette(&self, tones: &[u8]) -> Vec<Self> {
+        let (_, chroma, hue) = self.as_oklch().get();
+        tones
+            .iter()
+            .map(|tone| OklchPixel((*tone).min(100) as f32 / 100.0, chroma, hue).gamut_map(Gamut::Srgb))
+            .collect()
+    }
+
+    /// This function will build a gradient by mixing the current colour with another
+    /// using various ratios.
+    ///
+    /// `mixes` determines how many mixes get generated. Passing `1` will return
+    /// a vector with a single colour that mixes both equally.
     ///
     /// **Note:** This will *not* include either **pure** colour - only mixes.
     pub fn build_gradient_mix(&self, other: &RgbPixel, mixes: u16) -> Vec<Self> {
@@ -220,25 +1143,166 @@ impl RgbPixel {
         rgb_weighted_euclidean(self.get(), other.get())
     }
 
+    /// Compares the pixel to another using every distance algorithm at once - converting to
+    /// whichever colour space each algorithm needs.
+    pub fn compare(&self, other: &RgbPixel) -> ColourComparison {
+        let (lch_a, lch_b) = (self.as_lch().get(), other.as_lch().get());
+
+        ColourComparison {
+            weighted_euclidean: self.distance_from(other),
+            cie76: cie76(self.as_lab().get(), other.as_lab().get()),
+            cie94: cie94(lch_a, lch_b),
+            ciede2000: ciede2000(lch_a, lch_b),
+            delta_e_ok: delta_e_ok(self.as_oklab().get(), other.as_oklab().get()),
+        }
+    }
+
+    /// Checks whether the pixel is (close to) a grey - its OKLCH chroma is below `chroma_threshold`.
+    ///
+    /// The chroma of sRGB colours goes up to about `0.32`, so something around `0.02` works for "looks grey".
+    pub fn is_neutral(&self, chroma_threshold: f32) -> bool {
+        self.as_oklch().1 < chroma_threshold
+    }
+
+    /// Checks whether the pixel is colourful - its OKLCH chroma is at least `chroma_threshold`. The opposite of `is_neutral`.
+    pub fn is_saturated(&self, chroma_threshold: f32) -> bool {
+        !self.is_neutral(chroma_threshold)
+    }
+
+    /// Checks whether the pixel is light - its OKLAB lightness is at least `threshold` (from `0.0` to `1.0`).
+    pub fn is_light(&self, threshold: f32) -> bool {
+        self.as_oklab().0 >= threshold
+    }
+
+    /// Checks whether the pixel is dark - its OKLAB lightness is below `threshold`. The opposite of `is_light`.
+    pub fn is_dark(&self, threshold: f32) -> bool {
+        !self.is_light(threshold)
+    }
+
+    /// Retrieves the relative luminance of the pixel - the Y of XYZ, where black is `0.0` and white is `1.0`.
+    ///
+    /// The channels are decoded to linear light first, as WCAG defines it.
+    pub fn relative_luminance(&self) -> f32 {
+        chain_conversions(self.get(), &[srgb_to_linear_rgb, rgb_to_xyz_d65]).1
+    }
+
+    /// Retrieves the WCAG contrast ratio between two colours - from `1.0` (no contrast) to `21.0` (black on white).
+    /// The order doesn't matter.
+    pub fn contrast_ratio(&self, other: &RgbPixel) -> f32 {
+        let (a, b) = (self.relative_luminance(), other.relative_luminance());
+        (a.max(b) + 0.05) / (a.min(b) + 0.05)
+    }
+
+    /// Adjusts the (OKLCH) lightness of the pixel until it has at least `target_ratio` contrast with
+    /// `background` - for example, `4.5` for WCAG AA text.
+    ///
+    /// Whichever of lightening or darkening needs the smaller change is used, and the hue and chroma are
+    /// kept as far as the gamut allows. A pixel that already has enough contrast is returned unchanged.
+    /// If the ratio can't be reached at all, the extreme (towards black or white) with the most contrast is returned.
+    pub fn ensure_contrast(&self, background: &RgbPixel, target_ratio: f32) -> RgbPixel {
+        const ITERATIONS: usize = 24;
+
+        if self.contrast_ratio(background) >= target_ratio {
+            return *self;
+        }
+
+        let start = self.as_oklch();
+        let with_lightness = |lightness: f32| OklchPixel(lightness, start.1, start.2).gamut_map(Gamut::Srgb);
+
+        // finds the smallest change in lightness (towards `extreme`) that meets the ratio
+        let search = |extreme: f32| {
+            if with_lightness(extreme).contrast_ratio(background) < target_ratio {
+                return None;
+            }
+
+            let (mut low, mut high) = (start.0, extreme);
+            for _ in 0..ITERATIONS {
+                let middle = (low + high) / 2.0;
+                if with_lightness(middle).contrast_ratio(background) >= target_ratio {
+                    high = middle;
+                } else {
+                    low = middle;
+                }
+            }
+            Some(high)
+        };
+
+        match (search(0.0), search(1.0)) {
+            (Some(darker), Some(lighter)) if start.0 - darker <= lighter - start.0 => with_lightness(darker),
+            (_, Some(lighter)) => with_lightness(lighter),
+            (Some(darker), None) => with_lightness(darker),
+            (None, None) => {
+                let (black, white) = (with_lightness(0.0), with_lightness(1.0));
+                if black.contrast_ratio(background) >= white.contrast_ratio(background) { black } else { white }
+            }
+        }
+    }
+
+    /// Builds a dark and a light colour of the same hue and chroma (in OKLCH) with at least `target_ratio`
+    /// of contrast between them - such as text and background tokens for a theme. Returned as `(dark, light)`.
+    ///
+    /// The lightnesses spread out evenly from the middle of the contrast range, only as far as they need to.
+    /// Both colours are gamut-mapped, so the chroma drops where it has to. If the ratio can't be reached,
+    /// the pair with the most contrast (the darkest and lightest) is returned.
+    pub fn contrast_pair(hue: f32, chroma: f32, target_ratio: f32) -> (RgbPixel, RgbPixel) {
+        const ITERATIONS: usize = 24;
+        // the OKLAB lightness of the grey with equal contrast against black and white - its luminance is
+        // `sqrt(1.05 * 0.05) - 0.05`, and a grey's OKLAB lightness is the cube root of its luminance
+        const CENTRE: f32 = 0.564;
+
+        let pair = |spread: f32| (
+            OklchPixel((CENTRE - spread).max(0.0), chroma, hue).gamut_map(Gamut::Srgb),
+            OklchPixel((CENTRE + spread).min(1.0), chroma, hue).gamut_map(Gamut::Srgb),
+        );
+        let contrast = |(dark, light): (RgbPixel, RgbPixel)| dark.contrast_ratio(&light);
+
+        // finds the smallest spread that meets the ratio
+        let (mut low, mut high) = (0.0, CENTRE.max(1.0 - CENTRE));
+        if contrast(pair(high)) < target_ratio {
+            return pair(high);
+        }
+
+        for _ in 0..ITERATIONS {
+            let middle = (low + high) / 2.0;
+            if contrast(pair(middle)) >= target_ratio {
+                high = middle;
+            } else {
+                low = middle;
+            }
+        }
+
+        pair(high)
+    }
+
+    /// Retrieves the distance between two colours, using the given algorithm.
+    pub fn delta_e(&self, other: &RgbPixel, method: DeltaEMethod) -> f32 {
+        method.distance(method.prepare(self), method.prepare(other))
+    }
+
     /// Converts the pixel to an `HslPixel`.
+    #[must_use]
     pub fn as_hsl(&self) -> HslPixel {
         HslPixel::from_rgb(self)
     }
 
     /// Converts the pixel to a `LabPixel`.
+    #[must_use]
     pub fn as_lab(&self) -> LabPixel {
         LabPixel::from_rgb(self)
     }
 
     /// Converts the pixel to a `LchPixel`.
+    #[must_use]
     pub fn as_lch(&self) -> LchPixel {
         LchPixel::from_rgb(self)
     }
 
+    #[must_use]
     pub fn as_oklab(&self) -> OklabPixel {
         OklabPixel::from_rgb(self)
     }
 
+    #[must_use]
     pub fn as_oklch(&self) -> OklchPixel {
         OklchPixel::from_rgb(self)
     }
@@ -248,7 +1312,9 @@ impl RgbPixel {
 mod test {
     use std::time::Instant;
 
-    use super::RgbPixel;
+    use crate::{conversions::srgb_to_linear_rgb, palette::{golden_palette, PreparedPalette}, pixel::assert_close};
+
+    use super::{colours::{self, BLUE, RED}, ByLightness, Channel, DeltaEMethod, Gamut, InvalidByteLength, MixSpace, OklchPixel, RgbPixel, TieBreak, MATERIAL_TONES};
 
     const ITERATIONS: usize = 10_000;
     const TIME_SUFFIX: &'static str = "µs";
@@ -261,13 +1327,14 @@ mod test {
         benchmark_lch();
         benchmark_oklab();
         benchmark_oklch();
+        benchmark_quantize_perceptual();
     }
 
     fn benchmark_hsl() {
         let now = Instant::now();
 
         for _ in 1..ITERATIONS {
-            RGB_PIXEL.as_hsl();
+            let _ = RGB_PIXEL.as_hsl();
         }
 
         println!("HSL  : {}{}", now.elapsed().as_micros(), TIME_SUFFIX);
@@ -277,7 +1344,7 @@ mod test {
         let now = Instant::now();
 
         for _ in 1..ITERATIONS {
-            RGB_PIXEL.as_lab();
+            let _ = RGB_PIXEL.as_lab();
         }
 
         println!("LAB  : {}{}", now.elapsed().as_micros(), TIME_SUFFIX);
@@ -287,7 +1354,7 @@ mod test {
         let now = Instant::now();
 
         for _ in 1..ITERATIONS {
-            RGB_PIXEL.as_lch();
+            let _ = RGB_PIXEL.as_lch();
         }
 
         println!("LCH  : {}{}", now.elapsed().as_micros(), TIME_SUFFIX);
@@ -297,7 +1364,7 @@ mod test {
         let now = Instant::now();
 
         for _ in 1..ITERATIONS {
-            RGB_PIXEL.as_oklab();
+            let _ = RGB_PIXEL.as_oklab();
         }
 
         println!("OKLAB: {}{}", now.elapsed().as_micros(), TIME_SUFFIX);
@@ -307,9 +1374,660 @@ mod test {
         let now = Instant::now();
 
         for _ in 1..ITERATIONS {
-            RGB_PIXEL.as_oklch();
+            let _ = RGB_PIXEL.as_oklch();
         }
 
         println!("OKLCH: {}{}", now.elapsed().as_micros(), TIME_SUFFIX);
     }
-}
\ No newline at end of file
+
+    fn benchmark_quantize_perceptual() {
+        let palette = golden_palette(16, 0.7, 0.5, 0.0);
+        let prepared = PreparedPalette::new(&palette, DeltaEMethod::Ciede2000);
+        let pixel = RgbPixel(0.3, 0.6, 0.2);
+
+        let now = Instant::now();
+        for _ in 1..ITERATIONS {
+            let _ = pixel.quantize_perceptual(&palette, DeltaEMethod::Ciede2000);
+        }
+        println!("QUANTIZE (ΔE2000): {}{}", now.elapsed().as_micros(), TIME_SUFFIX);
+
+        let now = Instant::now();
+        for _ in 1..ITERATIONS {
+            let _ = prepared.quantize(&pixel);
+        }
+        println!("QUANTIZE (ΔE2000, prepared): {}{}", now.elapsed().as_micros(), TIME_SUFFIX);
+    }
+
+    #[test]
+    fn perceptual_midpoint_of_black_and_white() {
+        let midpoint = colours::BLACK.perceptual_midpoint(&colours::WHITE);
+        let (l, _, _) = midpoint.as_oklab().get();
+
+        assert!((l - 0.5).abs() < 1e-3);
+        assert!((midpoint.0 - 0.5).abs() > 0.1);
+    }
+
+    #[test]
+    fn perceptual_mean_of_primaries_is_grey() {
+        let mean = RgbPixel::perceptual_mean(&[colours::RED, colours::GREEN, colours::BLUE]);
+        let (_, chroma, _) = mean.as_oklch().get();
+
+        assert!(chroma < 0.03);
+    }
+
+    #[test]
+    fn compare_matches_individual_metrics() {
+        use crate::comparisons::{cie76, cie94, ciede2000, delta_e_ok, rgb_weighted_euclidean};
+
+        let (a, b) = (colours::ORANGE, colours::ROSE);
+        let comparison = a.compare(&b);
+
+        assert_eq!(comparison.weighted_euclidean, rgb_weighted_euclidean(a.get(), b.get()));
+        assert_eq!(comparison.cie76, cie76(a.as_lab().get(), b.as_lab().get()));
+        assert_eq!(comparison.cie94, cie94(a.as_lch().get(), b.as_lch().get()));
+        assert_eq!(comparison.ciede2000, ciede2000(a.as_lch().get(), b.as_lch().get()));
+        assert_eq!(comparison.delta_e_ok, delta_e_ok(a.as_oklab().get(), b.as_oklab().get()));
+    }
+
+    #[test]
+    fn tonal_ramp_is_more_saturated() {
+        let colour = colours::PINK;
+        let ramp = colour.build_tonal_ramp(5);
+        let gradient = colour.build_gradient_using_oklch(5);
+
+        assert_eq!(ramp.len(), 5);
+        for (tonal, shade) in ramp.iter().zip(gradient.iter()).skip(1).take(3) {
+            assert!(tonal.as_oklch().1 > shade.as_oklch().1);
+        }
+    }
+
+    #[test]
+    fn mix_preserve_lightness_keeps_target() {
+        let (a, b) = (colours::RED, colours::AQUAMARINE);
+
+        for ratio in [0.0, 0.25, 0.5, 0.75, 1.0] {
+            let mixed = a.mix_preserve_lightness(&b, ratio, Some(0.6));
+            assert!((mixed.as_oklab().0 - 0.6).abs() < 1e-3);
+        }
+
+        let (l_a, l_b) = (a.as_oklab().0, b.as_oklab().0);
+        let mixed = a.mix_preserve_lightness(&b, 0.25, None);
+        assert!((mixed.as_oklab().0 - (l_a * 0.25 + l_b * 0.75)).abs() < 1e-3);
+    }
+
+    #[test]
+    fn default_is_black() {
+        assert_eq!(RgbPixel::default().get(), colours::BLACK.get());
+    }
+
+    #[test]
+    fn tonal_palette_spans_black_to_white() {
+        let colour = colours::ORANGE;
+        let palette = colour.tonal_palette(&MATERIAL_TONES);
+
+        assert_close(palette[0].get(), colours::BLACK.get(), 1e-3);
+        assert_close(palette[10].get(), colours::WHITE.get(), 1e-3);
+
+        for pair in palette.windows(2) {
+            assert!(pair[1].as_oklab().0 > pair[0].as_oklab().0);
+        }
+        for tone in palette[2..9].iter() {
+            assert!((tone.as_oklch().2 - colour.as_oklch().2).abs() < 1.0);
+        }
+    }
+
+    #[test]
+    fn f64_construction_rounds_to_f32() {
+        let precise = (0.1_f64, 1.0_f64 / 3.0, 0.123456789012345_f64);
+        let pixel = RgbPixel::from(precise);
+
+        assert_eq!(pixel.get(), (precise.0 as f32, precise.1 as f32, precise.2 as f32));
+        assert_ne!(pixel.1 as f64, precise.1);
+    }
+
+    #[test]
+    fn wide_gamut_colours() {
+        use crate::conversions::{chain_conversions, display_p3_to_xyz_d65, linear_rgb_to_srgb, xyz_d65_to_rgb};
+
+        let p3_green: RgbPixel =
+            chain_conversions((0.1, 0.9, 0.1), &[display_p3_to_xyz_d65, xyz_d65_to_rgb, linear_rgb_to_srgb]).into();
+
+        assert!(!p3_green.is_in_gamut(Gamut::Srgb));
+        assert!(p3_green.is_in_gamut(Gamut::DisplayP3));
+        assert!(p3_green.is_in_gamut(Gamut::Rec2020));
+
+        assert!(colours::RED.is_in_gamut(Gamut::Srgb));
+        assert!(colours::RED.is_in_gamut(Gamut::DisplayP3));
+    }
+
+    #[test]
+    fn from_hash_is_deterministic() {
+        use std::hash::Hasher;
+
+        // integers are hashed the same, whatever the platform's endianness and pointer width
+        let (mut native, mut canonical) = (super::FnvHasher::new(), super::FnvHasher::new());
+        native.write_usize(0x0102_0304);
+        canonical.write(&[4, 3, 2, 1, 0, 0, 0, 0]);
+        assert_eq!(native.finish(), canonical.finish());
+
+        assert_eq!(RgbPixel::from_hash("alice").get(), RgbPixel::from_hash("alice").get());
+        assert_eq!(RgbPixel::from_hash(&42_u32).get(), RgbPixel::from_hash(&42_u32).get());
+
+        let names = ["alice", "bob", "carol", "dave", "eve"];
+        let colours: Vec<(u8, u8, u8)> = names.iter().map(|name| RgbPixel::from_hash(*name).get_u8()).collect();
+        for (i, a) in colours.iter().enumerate() {
+            for b in colours.iter().skip(i + 1) {
+                assert_ne!(a, b);
+            }
+        }
+    }
+
+    #[test]
+    fn rgb8_round_trip() {
+        let bytes = [255, 0, 0, 12, 34, 56, 0, 0, 255];
+        let pixels = RgbPixel::from_rgb8_slice(&bytes).unwrap();
+
+        assert_eq!(pixels.len(), 3);
+        assert_eq!(pixels[1].get_u8(), (12, 34, 56));
+        assert_eq!(RgbPixel::to_rgb8_vec(&pixels), bytes);
+
+        let mut out = [0; 4];
+        colours::CYAN.write_rgb8(&mut out);
+        assert_eq!(out, [0, 255, 255, 0]);
+        assert_eq!(RgbPixel::read_rgb8(&out).get_u8(), (0, 255, 255));
+    }
+
+    #[test]
+    fn rgb8_slice_rejects_leftover_bytes() {
+        assert_eq!(RgbPixel::from_rgb8_slice(&[255, 0, 0, 0, 255]).unwrap_err(), InvalidByteLength { expected: 3, found: 5 });
+        assert_eq!(RgbPixel::from_rgb8_slice(&[0]).unwrap_err(), InvalidByteLength { expected: 0, found: 1 });
+        assert!(RgbPixel::from_rgb8_slice(&[]).unwrap().is_empty());
+    }
+
+    #[test]
+    #[should_panic]
+    fn read_rgb8_rejects_short_slices() {
+        RgbPixel::read_rgb8(&[255, 0]);
+    }
+
+    #[test]
+    fn rank_palette_starts_with_quantized() {
+        let palette = [colours::BLACK, colours::RED, colours::ORANGE, colours::GOLD, colours::WHITE];
+        let ranking = colours::RUST.rank_palette(&palette);
+
+        assert_eq!(ranking.len(), palette.len());
+        assert_eq!(Some(ranking[0].0), colours::RUST.quantize_index(&palette));
+        assert!(ranking.windows(2).all(|pair| pair[0].1 <= pair[1].1));
+        assert_eq!(RgbPixel(0.5, 0.5, 0.5).quantize_index(&[]), None);
+    }
+
+    #[test]
+    fn quantize_soft_blends_nearest() {
+        let palette = [colours::BLACK, colours::WHITE, colours::RED];
+        let grey = RgbPixel(0.5, 0.5, 0.5);
+
+        assert_close(grey.quantize_soft(&palette, 1.0).get(), grey.get(), 1e-3);
+
+        let dark = RgbPixel(0.3, 0.3, 0.3);
+        let soft = dark.quantize_soft(&palette, 1.0);
+        let sharp = dark.quantize_soft(&palette, 50.0);
+        assert!(soft.0 > 0.01);
+        assert_close(sharp.get(), dark.quantize(&palette).get(), 1e-3);
+    }
+
+    #[test]
+    fn quantize_soft_survives_high_sharpness() {
+        let palette = [colours::BLACK, colours::WHITE];
+        let near_black = RgbPixel(0.001, 0.001, 0.001);
+
+        for sharpness in [10.0, 20.0, 50.0, 100.0, 1000.0] {
+            assert_eq!(near_black.quantize_soft(&palette, sharpness).get(), colours::BLACK.get(), "sharpness {}", sharpness);
+        }
+        assert_close(near_black.quantize_soft(&palette, 0.0).get(), (0.5, 0.5, 0.5), 1e-3);
+    }
+
+    #[test]
+    fn hdr_survives_round_trips() {
+        let hdr = RgbPixel(2.0, 0.5, 0.25);
+
+        assert_close(hdr.as_hsl().as_rgb().get(), hdr.get(), 1e-3);
+        assert_close(hdr.as_lab().as_rgb().get(), hdr.get(), 1e-3);
+        assert_close(hdr.as_lch().as_rgb().get(), hdr.get(), 1e-3);
+        assert_close(hdr.as_oklab().as_rgb().get(), hdr.get(), 1e-3);
+        assert_close(hdr.as_oklch().as_rgb().get(), hdr.get(), 1e-3);
+    }
+
+    #[test]
+    fn unclamped_operations_keep_hdr() {
+        let hdr = RgbPixel(2.0, 0.5, 0.25);
+
+        assert_close(hdr.add_error_unclamped((0.5, 0.0, 0.0)).get(), (2.5, 0.5, 0.25), 1e-3);
+        assert_close(hdr.add_error((0.5, 0.0, 0.0)).get(), (1.0, 0.5, 0.25), 1e-3);
+
+        let black = RgbPixel(0.0, 0.0, 0.0);
+        assert_close(hdr.mix_unclamped(1.5, &black).get(), (3.0, 0.75, 0.375), 1e-3);
+        assert_close(hdr.mix(1.5, &black).get(), hdr.get(), 1e-3);
+    }
+
+    #[test]
+    fn white_balance_identity() {
+        let pixel = RgbPixel(0.8, 0.4, 0.2);
+
+        assert_close(pixel.white_balance(0.0, 0.0).get(), pixel.get(), 1e-3);
+    }
+
+    #[test]
+    fn white_balance_warms_and_tints() {
+        let grey = RgbPixel(0.5, 0.5, 0.5);
+
+        let (r, _, b) = grey.white_balance(50.0, 0.0).get();
+        assert!(r > 0.5 && b < 0.5, "expected amber, got ({}, {})", r, b);
+
+        let (r, _, b) = grey.white_balance(-50.0, 0.0).get();
+        assert!(r < 0.5 && b > 0.5, "expected blue, got ({}, {})", r, b);
+
+        let (r, g, b) = grey.white_balance(0.0, 0.01).get();
+        assert!(g < r && g < b, "expected magenta, got ({}, {}, {})", r, g, b);
+    }
+
+    #[test]
+    fn lerp_hits_endpoints() {
+        let (a, b) = (RgbPixel(0.2, 0.4, 0.6), RgbPixel(1.0, 0.0, 0.5));
+
+        assert_eq!(RgbPixel::lerp(&a, &b, 0.0).get(), a.get());
+        assert_eq!(RgbPixel::lerp(&a, &b, 1.0).get(), b.get());
+        assert_close(RgbPixel::lerp(&a, &b, 0.5).get(), (0.6, 0.2, 0.55), 1e-3);
+    }
+
+    #[test]
+    fn try_from_bytes_checks_length() {
+        let bytes: &[u8] = &[255, 0, 51];
+        assert_eq!(RgbPixel::try_from(bytes).map(|pixel| pixel.get()), Ok((1.0, 0.0, 0.2)));
+
+        let short: &[u8] = &[255, 0];
+        assert_eq!(RgbPixel::try_from(short).unwrap_err(), InvalidByteLength { expected: 3, found: 2 });
+
+        let long: &[u8] = &[255, 0, 51, 255];
+        assert_eq!(RgbPixel::try_from(long).unwrap_err(), InvalidByteLength { expected: 3, found: 4 });
+    }
+
+    #[test]
+    fn wheel_round_trips() {
+        let (angle, radius) = RED.to_wheel();
+        assert!((angle - RED.as_oklch().2.to_radians()).abs() < 1e-5);
+        assert!(radius > 0.0 && radius <= 1.0);
+
+        for pixel in [RED, colours::AQUAMARINE, RgbPixel(0.3, 0.4, 0.6)] {
+            let (angle, radius) = pixel.to_wheel();
+            let round_trip = RgbPixel::from_wheel(angle, radius, pixel.as_oklch().0);
+            assert!(round_trip.delta_e(&pixel, DeltaEMethod::Ok) < 1e-3, "{:?} became {:?}", pixel, round_trip);
+        }
+
+        assert_eq!(colours::WHITE.to_wheel().0, 0.0);
+    }
+
+    #[test]
+    fn rotate_hue_batch_shifts_chromatic_pixels() {
+        let original = [RgbPixel(0.6, 0.4, 0.4), RgbPixel(0.4, 0.5, 0.4), colours::WHITE, RgbPixel(0.4, 0.4, 0.6), RgbPixel(0.3, 0.3, 0.3)];
+        let mut pixels = original;
+        RgbPixel::rotate_hue_batch(&mut pixels, 40.0);
+
+        for (before, after) in original.iter().zip(pixels.iter()) {
+            let (before, after) = (before.as_oklch(), after.as_oklch());
+            if before.2.is_nan() {
+                assert!(after.2.is_nan());
+                continue;
+            }
+
+            let shift = (after.2 - before.2).rem_euclid(360.0);
+            assert!((shift - 40.0).abs() < 0.1, "{:?} became {:?}", before, after);
+            assert!((after.0 - before.0).abs() < 1e-3 && (after.1 - before.1).abs() < 1e-3);
+        }
+
+        assert_eq!(pixels[2].get(), colours::WHITE.get());
+        assert_eq!(pixels[4].get(), (0.3, 0.3, 0.3));
+    }
+
+    #[test]
+    fn adjust_exposure_scales_linear_light() {
+        let pixel = RgbPixel(0.4, 0.3, 0.2);
+        let (before, after) = (srgb_to_linear_rgb(pixel.get()), srgb_to_linear_rgb(pixel.adjust_exposure(1.0).get()));
+
+        assert!((after.0 - before.0 * 2.0).abs() < 1e-5);
+        assert!((after.1 - before.1 * 2.0).abs() < 1e-5);
+        assert!((after.2 - before.2 * 2.0).abs() < 1e-5);
+
+        let (r, g, b) = RgbPixel(0.5, 0.5, 0.5).adjust_exposure(-1.5).get();
+        assert!(r == g && g == b);
+
+        // HDR values are kept
+        assert!(colours::WHITE.adjust_exposure(1.0).0 > 1.0);
+    }
+
+    #[test]
+    fn classification_predicates() {
+        let grey = RgbPixel(0.5, 0.5, 0.5);
+
+        assert!(grey.is_neutral(0.02) && !grey.is_saturated(0.02));
+        assert!(RED.is_saturated(0.02) && !RED.is_neutral(0.02));
+        assert!(colours::WHITE.is_light(0.5) && colours::BLACK.is_dark(0.5));
+
+        // exactly on the threshold counts as saturated (and light)
+        let chroma = RED.as_oklch().1;
+        assert!(RED.is_saturated(chroma) && !RED.is_neutral(chroma));
+
+        let lightness = grey.as_oklab().0;
+        assert!(grey.is_light(lightness) && !grey.is_dark(lightness));
+    }
+
+    #[test]
+    fn scale_to_fit_gamut_keeps_hue() {
+        let hdr = RgbPixel(2.0, 1.0, 0.0);
+
+        assert_eq!(hdr.scale_to_fit_gamut().get(), (1.0, 0.5, 0.0));
+        assert_eq!(hdr.clamp().get(), (1.0, 1.0, 0.0));
+        assert!((hdr.scale_to_fit_gamut().as_hsl().0 - hdr.as_hsl().0).abs() < 1e-3);
+
+        assert_eq!(RgbPixel(0.5, 0.2, 0.1).scale_to_fit_gamut().get(), (0.5, 0.2, 0.1));
+    }
+
+    #[test]
+    fn adjust_saturation_soft_rolls_off() {
+        // a dull colour has lots of headroom, so a small boost is close to linear
+        let dull = OklchPixel(0.6, 0.03, 200.0).as_rgb();
+        let boosted = dull.adjust_saturation_soft(1.2).as_oklch().1;
+        assert!((boosted - dull.as_oklch().1 * 1.2).abs() < 1e-3, "{}", boosted);
+
+        // a saturated colour (just inside the gamut, so it has a little headroom) is compressed against the edge instead
+        let saturated = RgbPixel(0.85, 0.15, 0.15);
+        let boosted = saturated.adjust_saturation_soft(1.5);
+        assert!(boosted.as_oklch().1 > saturated.as_oklch().1);
+        assert!(boosted.as_oklch().1 < saturated.as_oklch().1 * 1.5);
+
+        for pixel in [dull, saturated, RED, colours::AQUAMARINE, RgbPixel(0.3, 0.6, 0.9)] {
+            for factor in [0.0, 0.5, 1.5, 3.0, 10.0] {
+                assert!(pixel.adjust_saturation_soft(factor).is_in_gamut(Gamut::Srgb), "{:?} * {}", pixel, factor);
+            }
+            assert_eq!(pixel.adjust_saturation_soft(1.0).get(), pixel.get());
+        }
+    }
+
+    #[test]
+    fn contrast_pair_meets_target() {
+        for (hue, target) in [(250.0, 4.5), (30.0, 7.0), (140.0, 3.0)] {
+            let (dark, light) = RgbPixel::contrast_pair(hue, 0.08, target);
+
+            assert!(dark.contrast_ratio(&light) >= target, "{} at {}", dark.contrast_ratio(&light), hue);
+            assert!(dark.contrast_ratio(&light) < target + 0.01);
+            assert!(dark.relative_luminance() < light.relative_luminance());
+
+            for pixel in [dark, light] {
+                let difference = (pixel.as_oklch().2 - hue + 180.0).rem_euclid(360.0) - 180.0;
+                assert!(difference.abs() < 1.0, "{:?} should have a hue of {}", pixel.as_oklch(), hue);
+            }
+        }
+
+        // an impossible ratio gives the most contrast available
+        let (dark, light) = RgbPixel::contrast_pair(250.0, 0.0, 30.0);
+        assert!(dark.contrast_ratio(&light) > 20.0);
+    }
+
+    #[test]
+    fn from_i32_clamps() {
+        let (r, g, b) = RgbPixel::from((-5, 300, 128)).get();
+
+        assert_eq!((r, g), (0.0, 1.0));
+        assert!((b - 0.502).abs() < 1e-3);
+    }
+
+    #[test]
+    fn tiebreak_policies() {
+        let pixel = RgbPixel(0.0, 0.5, 0.0);
+        let palette = [colours::GREEN, colours::BLACK, RED];
+        let pick = |palette: &[RgbPixel], policy| pixel.quantize_with_tiebreak(palette, policy).get();
+
+        assert_eq!(pick(&palette, TieBreak::First), colours::GREEN.get());
+        assert_eq!(pick(&palette, TieBreak::Last), colours::BLACK.get());
+        assert_eq!(pick(&palette, TieBreak::Darker), colours::BLACK.get());
+        assert_eq!(pick(&palette, TieBreak::Lighter), colours::GREEN.get());
+        assert_eq!(pixel.quantize(&palette).get(), pick(&palette, TieBreak::First));
+
+        // reordering only changes the order-based policies
+        let reordered = [colours::BLACK, RED, colours::GREEN];
+        assert_eq!(pick(&reordered, TieBreak::First), colours::BLACK.get());
+        assert_eq!(pick(&reordered, TieBreak::Last), colours::GREEN.get());
+        assert_eq!(pick(&reordered, TieBreak::Darker), colours::BLACK.get());
+        assert_eq!(pick(&reordered, TieBreak::Lighter), colours::GREEN.get());
+    }
+
+    #[test]
+    fn cie_mix_spaces() {
+        let midpoint = |space: MixSpace| space.lerp(&RED, &BLUE, 0.5);
+        let (cielab, oklab) = (midpoint(MixSpace::CieLab), midpoint(MixSpace::Oklab));
+
+        assert!(cielab.delta_e(&oklab, DeltaEMethod::Ok) > 0.01);
+
+        // the perceptual spaces step more evenly than RGB (judged by CIEDE2000, which neither is built on)
+        let unevenness = |space: MixSpace| {
+            let steps: Vec<f32> = (0..8)
+                .map(|i| space.lerp(&RED, &BLUE, i as f32 / 8.0).delta_e(&space.lerp(&RED, &BLUE, (i + 1) as f32 / 8.0), DeltaEMethod::Ciede2000))
+                .collect();
+            steps.iter().copied().fold(0.0, f32::max) / steps.iter().copied().fold(f32::MAX, f32::min)
+        };
+        assert!(unevenness(MixSpace::CieLab) < unevenness(MixSpace::Rgb));
+        assert!(unevenness(MixSpace::Oklab) < unevenness(MixSpace::Rgb));
+
+        // greys take on the other colour's hue in CIELCH
+        let tinted = MixSpace::CieLch.lerp(&colours::WHITE, &RED, 0.5).as_lch();
+        assert!((tinted.2 - RED.as_lch().2).abs() < 0.5);
+    }
+
+    #[test]
+    fn quantize_index_masked_skips_locked_entries() {
+        let palette = [RED, colours::ORANGE, BLUE];
+        let pixel = RgbPixel(0.9, 0.1, 0.0);
+
+        assert_eq!(pixel.quantize_index(&palette), Some(0));
+        assert_eq!(pixel.quantize_index_masked(&palette, &[true, true, true]), Some(0));
+        assert_eq!(pixel.quantize_index_masked(&palette, &[false, true, true]), Some(1));
+        assert_eq!(pixel.quantize_index_masked(&palette, &[false, false, false]), None);
+    }
+
+    #[test]
+    fn mix_array_samples_stops() {
+        let stops = [RED, colours::GREEN, BLUE];
+
+        for space in [MixSpace::Rgb, MixSpace::LinearRgb, MixSpace::Oklab] {
+            assert_eq!(RgbPixel::mix_array(&stops, 0.0, space).get(), RED.get());
+            assert_eq!(RgbPixel::mix_array(&stops, 0.5, space).get(), colours::GREEN.get());
+            assert_eq!(RgbPixel::mix_array(&stops, 1.0, space).get(), BLUE.get());
+        }
+
+        assert_eq!(RgbPixel::mix_array(&stops, 0.25, MixSpace::Rgb).get(), (0.5, 0.5, 0.0));
+        assert_eq!(RgbPixel::mix_array(&[], 0.5, MixSpace::Rgb).get(), colours::BLACK.get());
+    }
+
+    #[test]
+    fn channel_as_mono_extracts_channels() {
+        assert_eq!(RED.channel_as_mono(Channel::Red).get(), 255);
+        assert_eq!(BLUE.channel_as_mono(Channel::Red).get(), 0);
+
+        let split = RgbPixel(1.0, 0.5, 0.0).split_channels().map(|mono| mono.get());
+        assert_eq!(split, [255, 128, 0]);
+    }
+
+    #[test]
+    fn decolorize_separates_iso_luminant_colours() {
+        let warm = OklchPixel(0.7, 0.1, 60.0).gamut_map(Gamut::Srgb);
+        let cool = OklchPixel(0.7, 0.1, 240.0).gamut_map(Gamut::Srgb);
+
+        let plain = (warm.grayscale_decolorize(0.0).get(), cool.grayscale_decolorize(0.0).get());
+        assert!(plain.0.abs_diff(plain.1) <= 1, "{:?}", plain);
+
+        let decolorized = (warm.grayscale_decolorize(0.5).get(), cool.grayscale_decolorize(0.5).get());
+        assert!(decolorized.0 > decolorized.1 + 10, "{:?}", decolorized);
+
+        assert_eq!(colours::WHITE.grayscale_decolorize(1.0).get(), 255);
+        assert_eq!(colours::BLACK.grayscale_decolorize(1.0).get(), 0);
+    }
+
+    #[test]
+    fn hue_cycle_rotates_evenly() {
+        let start = colours::ORANGE;
+        let start_hue = start.as_oklch().2;
+        let cycle: Vec<RgbPixel> = start.hue_cycle(6).collect();
+
+        assert_eq!(cycle.len(), 6);
+        assert_eq!(cycle[0].get(), start.get());
+
+        // the last colour is one step short of a full rotation
+        let last_hue = cycle[5].as_oklch().2;
+        let expected = (start_hue - 60.0).rem_euclid(360.0);
+        assert!((last_hue - expected).abs() < 0.5, "{} != {}", last_hue, expected);
+    }
+
+    #[test]
+    fn hue_cycle_repeats_greys() {
+        let grey = RgbPixel(0.5, 0.5, 0.5);
+
+        assert_eq!(grey.hue_cycle(4).count(), 4);
+        assert!(grey.hue_cycle(4).all(|colour| colour.get() == grey.get()));
+        assert_eq!(colours::RED.hue_cycle(0).count(), 0);
+    }
+
+    #[test]
+    fn per_channel_min_max_clamp() {
+        let (a, b) = (RgbPixel(0.2, 0.8, 0.5), RgbPixel(0.6, 0.1, 0.5));
+
+        assert_eq!(a.min_channels(&b).get(), (0.2, 0.1, 0.5));
+        assert_eq!(a.max_channels(&b).get(), (0.6, 0.8, 0.5));
+        assert_eq!(colours::WHITE.min_channels(&colours::RED).get(), colours::RED.get());
+        assert_eq!(colours::BLACK.max_channels(&colours::RED).get(), colours::RED.get());
+
+        let (lo, hi) = (RgbPixel(0.3, 0.3, 0.0), RgbPixel(0.7, 0.7, 0.4));
+        assert_eq!(RgbPixel(0.1, 0.9, 0.5).clamp_channels(&lo, &hi).get(), (0.3, 0.7, 0.4));
+        assert_eq!(RgbPixel(0.5, 0.5, 0.2).clamp_channels(&lo, &hi).get(), (0.5, 0.5, 0.2));
+    }
+
+    #[test]
+    fn sorts_by_lightness() {
+        let mut sorted: Vec<ByLightness> = [
+            colours::YELLOW,
+            colours::BLACK,
+            colours::PURPLE,
+            colours::WHITE,
+            colours::RED,
+            colours::BLUE,
+            colours::CYAN,
+            RgbPixel(0.5, 0.5, 0.5),
+        ].into_iter().map(ByLightness).collect();
+        sorted.sort();
+
+        let lightnesses: Vec<f32> = sorted.iter().map(|pixel| pixel.0.as_oklab().0).collect();
+        assert!(lightnesses.windows(2).all(|pair| pair[0] <= pair[1]), "{:?}", lightnesses);
+        assert_eq!(sorted[0].0.get(), colours::BLACK.get());
+        assert_eq!(sorted[7].0.get(), colours::WHITE.get());
+
+        assert!(ByLightness(colours::RED) == ByLightness(colours::RED));
+        assert!(ByLightness(colours::RED) != ByLightness(colours::BLUE));
+    }
+
+    #[test]
+    fn contrast_ratio_extremes() {
+        assert!((colours::BLACK.contrast_ratio(&colours::WHITE) - 21.0).abs() < 1e-3);
+        assert!((colours::WHITE.contrast_ratio(&colours::BLACK) - 21.0).abs() < 1e-3);
+        assert_eq!(colours::RED.contrast_ratio(&colours::RED), 1.0);
+
+        // WCAG's own example - #767676 is the lightest grey that passes AA on white
+        let grey = RgbPixel::from((0x76, 0x76, 0x76));
+        assert!((grey.contrast_ratio(&colours::WHITE) - 4.54).abs() < 0.01, "{}", grey.contrast_ratio(&colours::WHITE));
+        assert!((grey.relative_luminance() - 0.1812).abs() < 1e-3);
+
+        // the centre that `contrast_pair` spreads out from
+        let centre = OklchPixel(0.564, 0.0, 0.0).as_rgb();
+        assert!((centre.contrast_ratio(&colours::BLACK) - centre.contrast_ratio(&colours::WHITE)).abs() < 0.02);
+    }
+
+    #[test]
+    fn ensure_contrast_meets_ratio() {
+        let background = RgbPixel(0.9, 0.9, 0.9);
+        let foreground = RgbPixel(0.7, 0.4, 0.3);
+
+        let fixed = foreground.ensure_contrast(&background, 4.5);
+        assert!(fixed.contrast_ratio(&background) >= 4.5);
+        assert!(fixed.as_oklab().0 < foreground.as_oklab().0, "should darken against a light background");
+
+        let hue_difference = (fixed.as_oklch().2 - foreground.as_oklch().2).abs();
+        assert!(hue_difference < 2.0, "hue moved by {}", hue_difference);
+
+        let dark = RgbPixel(0.05, 0.02, 0.01);
+        assert_eq!(dark.ensure_contrast(&background, 4.5).get(), dark.get());
+    }
+
+    #[test]
+    fn snaps_tinted_white_to_planckian_locus() {
+        use crate::{conversions::{planckian_xy, uv_to_xy, xy_to_uv, xy_to_xyz}, pixel::xyz::XyzPixel};
+
+        // a 5000K white, pushed slightly towards green (perpendicular to the locus, above it)
+        let (u, v) = xy_to_uv(planckian_xy(5000.0));
+        let (next_u, next_v) = xy_to_uv(planckian_xy(5010.0));
+        let length = (next_u - u).hypot(next_v - v);
+        let (mut normal_u, mut normal_v) = (-(next_v - v) / length, (next_u - u) / length);
+        if normal_v < 0.0 {
+            (normal_u, normal_v) = (-normal_u, -normal_v);
+        }
+        let (x, y, z) = xy_to_xyz(uv_to_xy((u + normal_u * 0.005, v + normal_v * 0.005)));
+        let tinted = XyzPixel(x * 0.8, y * 0.8, z * 0.8).as_rgb();
+
+        let (snapped, cct) = tinted.snap_to_planckian();
+        assert!((cct - 5000.0).abs() < 50.0, "cct was {}", cct);
+
+        let duv = |pixel: &RgbPixel| {
+            let (su, sv) = xy_to_uv(XyzPixel::from_rgb(pixel).chromaticity());
+            let (lu, lv) = xy_to_uv(planckian_xy(cct));
+            (su - lu).hypot(sv - lv)
+        };
+        assert!(duv(&snapped) < 1e-4 && duv(&tinted) > 4e-3, "{} vs {}", duv(&snapped), duv(&tinted));
+        assert!((XyzPixel::from_rgb(&snapped).1 - 0.8).abs() < 1e-3);
+
+        assert!(colours::BLACK.snap_to_planckian().1.is_nan());
+    }
+
+    #[test]
+    fn complement_operators() {
+        let pixel = RgbPixel(0.2, 0.7, 1.2);
+
+        assert_close((-(-pixel)).get(), pixel.get(), 1e-3);
+        assert_close((!!pixel).get(), pixel.get(), 1e-3);
+        assert_close((!pixel).get(), (0.8, 0.3, -0.2), 1e-3);
+        assert_eq!((-colours::RED).get(), colours::CYAN.get());
+        assert_eq!((!colours::RED).get(), colours::CYAN.get());
+    }
+
+    #[test]
+    fn blend_over_is_linear() {
+        let (top, bottom) = (colours::RED, colours::BLUE);
+
+        assert_close(top.blend_over(&bottom, 1.0).get(), top.get(), 1e-3);
+        assert_close(top.blend_over(&bottom, 0.0).get(), bottom.get(), 1e-3);
+
+        let linear = colours::WHITE.blend_over(&colours::BLACK, 0.5);
+        let naive = colours::WHITE.mix(0.5, &colours::BLACK);
+        assert!(linear.0 > naive.0 + 0.1, "{:?} vs {:?}", linear, naive);
+    }
+
+    #[test]
+    fn round_to_is_stable() {
+        let noisy = RgbPixel(0.1 + 0.2, 0.700_000_1, -0.000_000_3);
+        let rounded = noisy.round_to(3);
+
+        assert_eq!(rounded.get(), (0.3, 0.7, 0.0));
+        assert!(rounded.2.is_sign_positive());
+        assert_eq!(format!("{:?}", rounded), "RgbPixel(0.3, 0.7, 0.0)");
+
+        let round_tripped = RgbPixel(0.8, 0.4, 0.2).as_lab().as_rgb();
+        assert_eq!(round_tripped.round_to(3).get(), (0.8, 0.4, 0.2));
+        assert_eq!(round_tripped.round_to(3).get(), round_tripped.round_to(3).round_to(3).get());
+        assert_eq!(noisy.round_to(0).get(), (0.0, 1.0, 0.0));
+    }
+}
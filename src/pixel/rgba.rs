@@ -0,0 +1,191 @@
+use std::ops::RangeInclusive;
+
+use super::{oklab::OklabPixel, rgb::RgbPixel, InvalidByteLength, round_decimals};
+
+#[derive(Debug, Clone, Copy)]
+/// Represents a pixel in the RGB colour space, with an extra alpha (opacity) channel.
+/// Each value (RGBA) ranges between 0.0 and 1.0 - where an alpha of `0.0` is fully transparent.
+///
+/// `Default` is opaque black - `(0.0, 0.0, 0.0, 1.0)` - rather than an error value.
+pub struct RgbaPixel(pub f32, pub f32, pub f32, pub f32);
+
+impl From<(u8, u8, u8, u8)> for RgbaPixel {
+    fn from(value: (u8, u8, u8, u8)) -> Self {
+        RgbaPixel(
+            value.0 as f32 / 255.0,
+            value.1 as f32 / 255.0,
+            value.2 as f32 / 255.0,
+            value.3 as f32 / 255.0,
+        )
+    }
+}
+
+/// Reads exactly 4 bytes - red, green, blue, and alpha.
+impl TryFrom<&[u8]> for RgbaPixel {
+    type Error = InvalidByteLength;
+
+    fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
+        match *value {
+            [r, g, b, a] => Ok((r, g, b, a).into()),
+            _ => Err(InvalidByteLength { expected: 4, found: value.len() }),
+        }
+    }
+}
+
+impl From<(f32, f32, f32, f32)> for RgbaPixel {
+    fn from(value: (f32, f32, f32, f32)) -> Self {
+        RgbaPixel(value.0, value.1, value.2, value.3)
+    }
+}
+
+/// Each component is rounded to the nearest `f32`.
+impl From<(f64, f64, f64, f64)> for RgbaPixel {
+    fn from(value: (f64, f64, f64, f64)) -> Self {
+        RgbaPixel(value.0 as f32, value.1 as f32, value.2 as f32, value.3 as f32)
+    }
+}
+
+impl From<RgbPixel> for RgbaPixel {
+    fn from(value: RgbPixel) -> Self {
+        RgbaPixel(value.0, value.1, value.2, 1.0)
+    }
+}
+
+impl Default for RgbaPixel {
+    fn default() -> Self {
+        RgbaPixel(0.0, 0.0, 0.0, 1.0)
+    }
+}
+
+impl RgbaPixel {
+    /// The valid range of the red channel.
+    pub const R_RANGE: RangeInclusive<f32> = 0.0..=1.0;
+    /// The valid range of the green channel.
+    pub const G_RANGE: RangeInclusive<f32> = 0.0..=1.0;
+    /// The valid range of the blue channel.
+    pub const B_RANGE: RangeInclusive<f32> = 0.0..=1.0;
+    /// The valid range of the alpha channel.
+    pub const A_RANGE: RangeInclusive<f32> = 0.0..=1.0;
+
+    /// Retrieves the (r, g, b, a) channels of the pixel as a tuple.
+    pub fn get(&self) -> (f32, f32, f32, f32) {
+        (self.0, self.1, self.2, self.3)
+    }
+
+    /// Rounds each component to `decimals` decimal places. See `RgbPixel::round_to`.
+    #[must_use]
+    pub fn round_to(&self, decimals: u32) -> Self {
+        RgbaPixel(round_decimals(self.0, decimals), round_decimals(self.1, decimals), round_decimals(self.2, decimals), round_decimals(self.3, decimals))
+    }
+
+    /// Linearly interpolates between `a` and `b`, alpha included. See `RgbPixel::lerp`.
+    pub fn lerp(a: &RgbaPixel, b: &RgbaPixel, t: f32) -> RgbaPixel {
+        let lerp = |start: f32, end: f32| start + (end - start) * t;
+        RgbaPixel(lerp(a.0, b.0), lerp(a.1, b.1), lerp(a.2, b.2), lerp(a.3, b.3))
+    }
+
+    /// Mixes the pixel with another. The ratio works the same way as in `RgbPixel::mix` - it's the weight of this
+    /// pixel, so `1.0` returns this pixel, and `0.0` returns `other`.
+    ///
+    /// The colour is mixed in OKLAB with premultiplied alpha, and the alpha is mixed linearly. This way, a
+    /// (nearly) transparent colour barely affects the mix - so fading red to transparent stays red until it's
+    /// gone, rather than passing through the transparent colour's hue. The ratio is clamped.
+    pub fn mix_oklab(&self, other: &RgbaPixel, ratio: f32) -> RgbaPixel {
+        // how far to move from this pixel towards `other`
+        let ratio = 1.0 - ratio.clamp(0.0, 1.0);
+        let (a, b) = (self.rgb().as_oklab(), other.rgb().as_oklab());
+
+        let alpha = self.3 + (other.3 - self.3) * ratio;
+        let premultiplied = |start: f32, end: f32| start * self.3 + (end * other.3 - start * self.3) * ratio;
+
+        // with no alpha, there's nothing to un-premultiply - so mix the colours as-is
+        let colour = if alpha == 0.0 {
+            OklabPixel::lerp(&a, &b, ratio)
+        } else {
+            OklabPixel(
+                premultiplied(a.0, b.0) / alpha,
+                premultiplied(a.1, b.1) / alpha,
+                premultiplied(a.2, b.2) / alpha,
+            )
+        };
+
+        let RgbPixel(r, g, b) = colour.as_rgb();
+        RgbaPixel(r, g, b, alpha)
+    }
+
+    /// Retrieves the colour of the pixel, ignoring the alpha.
+    pub fn rgb(&self) -> RgbPixel {
+        RgbPixel(self.0, self.1, self.2)
+    }
+
+    /// Retrieves the alpha of the pixel.
+    pub fn alpha(&self) -> f32 {
+        self.3
+    }
+
+    /// Quantizes the colour of the pixel to the nearest colour in an (opaque) palette.
+    /// The alpha is kept as-is.
+    pub fn quantize(&self, palette: &[RgbPixel]) -> RgbaPixel {
+        let RgbPixel(r, g, b) = self.rgb().quantize(palette);
+        RgbaPixel(r, g, b, self.3)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::pixel::{oklab::OklabPixel, rgb::{colours::{BLUE, RED}, DeltaEMethod}, InvalidByteLength};
+
+    use super::RgbaPixel;
+
+    #[test]
+    fn quantize_keeps_alpha() {
+        let pixel = RgbaPixel(0.9, 0.1, 0.1, 0.25);
+        let quantized = pixel.quantize(&[RED, BLUE]);
+
+        assert_eq!(quantized.get(), (1.0, 0.0, 0.0, 0.25));
+    }
+
+    #[test]
+    fn mix_oklab_fades_without_bleeding() {
+        let red = RgbaPixel::from(RED);
+        let transparent = RgbaPixel(0.0, 0.0, 1.0, 0.0);
+
+        let midpoint = red.mix_oklab(&transparent, 0.5);
+        assert!((midpoint.alpha() - 0.5).abs() < 1e-6);
+        assert!(midpoint.rgb().delta_e(&RED, DeltaEMethod::Ok) < 1e-3, "{:?}", midpoint);
+
+        // a straight mix passes through purple instead
+        let naive = RgbaPixel::lerp(&red, &transparent, 0.5);
+        assert!(naive.rgb().delta_e(&RED, DeltaEMethod::Ok) > 0.1);
+
+        // mostly red is mostly opaque
+        assert!((red.mix_oklab(&transparent, 0.75).alpha() - 0.75).abs() < 1e-6);
+    }
+
+    #[test]
+    fn mix_oklab_of_opaque_colours() {
+        let (red, blue) = (RgbaPixel::from(RED), RgbaPixel::from(BLUE));
+        let mixed = red.mix_oklab(&blue, 0.25);
+        let expected = OklabPixel::lerp(&RED.as_oklab(), &BLUE.as_oklab(), 0.75).as_rgb();
+
+        assert_eq!(mixed.alpha(), 1.0);
+        assert!(mixed.rgb().delta_e(&expected, DeltaEMethod::Ok) < 1e-3);
+
+        // like `RgbPixel::mix`, the ratio is the weight of `self`
+        assert!(red.mix_oklab(&blue, 1.0).rgb().delta_e(&RED, DeltaEMethod::Ok) < 1e-3);
+        assert!(red.mix_oklab(&blue, 0.0).rgb().delta_e(&BLUE, DeltaEMethod::Ok) < 1e-3);
+        assert!(red.mix_oklab(&blue, 1.0).rgb().delta_e(&RED.mix(1.0, &BLUE), DeltaEMethod::Ok) < 1e-3);
+    }
+
+    #[test]
+    fn try_from_bytes_checks_length() {
+        let bytes: &[u8] = &[255, 0, 51, 0];
+        assert_eq!(RgbaPixel::try_from(bytes).map(|pixel| pixel.get()), Ok((1.0, 0.0, 0.2, 0.0)));
+
+        let short: &[u8] = &[255, 0, 51];
+        assert_eq!(RgbaPixel::try_from(short).unwrap_err(), InvalidByteLength { expected: 4, found: 3 });
+
+        let long: &[u8] = &[255, 0, 51, 0, 0];
+        assert_eq!(RgbaPixel::try_from(long).unwrap_err(), InvalidByteLength { expected: 4, found: 5 });
+    }
+}
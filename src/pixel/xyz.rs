@@ -0,0 +1,78 @@
+use std::ops::RangeInclusive;
+
+use crate::conversions::{chain_conversions, linear_rgb_to_srgb, rgb_to_xyz_d65, srgb_to_linear_rgb, xyz_d65_to_rgb};
+
+use super::{rgb::RgbPixel, round_decimals};
+
+#[derive(Debug, Clone, Copy, Default)]
+/// Represents a pixel in the CIE XYZ colour space, relative to a D65 white. The 3 components are:
+///
+/// - X: Roughly ranges from 0.0 to 0.95. A mix of the cone responses.
+/// - Y: Ranges from 0.0 to 1.0. Determines the luminance of the pixel.
+/// - Z: Roughly ranges from 0.0 to 1.09. Roughly represents the blue cone response.
+///
+/// XYZ isn't very useful to work with directly - but most other colour spaces are defined from it,
+/// and it's what physical measurements (like spectra) produce.
+pub struct XyzPixel(pub f32, pub f32, pub f32);
+
+impl From<(f32, f32, f32)> for XyzPixel {
+    fn from(value: (f32, f32, f32)) -> Self {
+        let (x, y, z) = value;
+        XyzPixel(x, y, z)
+    }
+}
+
+/// Each component is rounded to the nearest `f32`.
+impl From<(f64, f64, f64)> for XyzPixel {
+    fn from(value: (f64, f64, f64)) -> Self {
+        XyzPixel(value.0 as f32, value.1 as f32, value.2 as f32)
+    }
+}
+
+impl From<RgbPixel> for XyzPixel {
+    fn from(value: RgbPixel) -> Self {
+        Self::from_rgb(&value)
+    }
+}
+
+impl XyzPixel {
+    /// The range of X, up to the D65 white.
+    pub const X_RANGE: RangeInclusive<f32> = 0.0..=0.9505;
+    /// The valid range of the luminance.
+    pub const Y_RANGE: RangeInclusive<f32> = 0.0..=1.0;
+    /// The range of Z, up to the D65 white.
+    pub const Z_RANGE: RangeInclusive<f32> = 0.0..=1.0891;
+
+    pub fn get(&self) -> (f32, f32, f32) {
+        (self.0, self.1, self.2)
+    }
+
+    /// Rounds each component to `decimals` decimal places. See `RgbPixel::round_to`.
+    #[must_use]
+    pub fn round_to(&self, decimals: u32) -> Self {
+        XyzPixel(round_decimals(self.0, decimals), round_decimals(self.1, decimals), round_decimals(self.2, decimals))
+    }
+
+    /// Linearly interpolates between `a` and `b`. See `RgbPixel::lerp`.
+    pub fn lerp(a: &XyzPixel, b: &XyzPixel, t: f32) -> XyzPixel {
+        let lerp = |start: f32, end: f32| start + (end - start) * t;
+        XyzPixel(lerp(a.0, b.0), lerp(a.1, b.1), lerp(a.2, b.2))
+    }
+
+    /// Retrieves the (x, y) chromaticity coordinates - the colour of the pixel, regardless of its luminance.
+    ///
+    /// Black has no chromaticity, and returns `(NaN, NaN)`.
+    pub fn chromaticity(&self) -> (f32, f32) {
+        let sum = self.0 + self.1 + self.2;
+        (self.0 / sum, self.1 / sum)
+    }
+
+    pub fn from_rgb(rgb: &RgbPixel) -> XyzPixel {
+        chain_conversions(rgb.get(), &[srgb_to_linear_rgb, rgb_to_xyz_d65]).into()
+    }
+
+    #[must_use]
+    pub fn as_rgb(&self) -> RgbPixel {
+        chain_conversions(self.get(), &[xyz_d65_to_rgb, linear_rgb_to_srgb]).into()
+    }
+}
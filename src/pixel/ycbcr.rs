@@ -0,0 +1,129 @@
+use std::ops::RangeInclusive;
+
+use crate::conversions::{rgb_to_ycbcr, ycbcr_to_rgb, YccMatrix};
+
+use super::{rgb::RgbPixel, round_decimals};
+
+#[derive(Debug, Clone, Copy, Default)]
+/// Represents a pixel in (full-range) YCbCr - the luma, and the blue and red chroma differences used by
+/// video. Each component ranges between 0.0 and 1.0, with the chroma centred on `0.5` (so greys have
+/// a Cb and Cr of `0.5`).
+///
+/// The pixel doesn't remember which `YccMatrix` it was made with, so the same one has to be given to `as_rgb`.
+///
+/// `Default` is `(0.0, 0.0, 0.0)` - which isn't black, since the chroma isn't centred.
+pub struct YcbcrPixel(pub f32, pub f32, pub f32);
+
+impl From<(f32, f32, f32)> for YcbcrPixel {
+    fn from(value: (f32, f32, f32)) -> Self {
+        let (y, cb, cr) = value;
+        YcbcrPixel(y, cb, cr)
+    }
+}
+
+/// Each component is rounded to the nearest `f32`.
+impl From<(f64, f64, f64)> for YcbcrPixel {
+    fn from(value: (f64, f64, f64)) -> Self {
+        YcbcrPixel(value.0 as f32, value.1 as f32, value.2 as f32)
+    }
+}
+
+impl YcbcrPixel {
+    /// The valid range of the luma.
+    pub const Y_RANGE: RangeInclusive<f32> = 0.0..=1.0;
+    /// The valid range of the blue difference.
+    pub const CB_RANGE: RangeInclusive<f32> = 0.0..=1.0;
+    /// The valid range of the red difference.
+    pub const CR_RANGE: RangeInclusive<f32> = 0.0..=1.0;
+
+    pub fn get(&self) -> (f32, f32, f32) {
+        (self.0, self.1, self.2)
+    }
+
+    /// Rounds each component to `decimals` decimal places. See `RgbPixel::round_to`.
+    #[must_use]
+    pub fn round_to(&self, decimals: u32) -> Self {
+        YcbcrPixel(round_decimals(self.0, decimals), round_decimals(self.1, decimals), round_decimals(self.2, decimals))
+    }
+
+    /// Converts the pixel to 8-bit limited ("studio") range, as used by broadcast video and most codecs -
+    /// where the luma goes from `16` to `235`, and the chroma from `16` to `240` (centred on `128`).
+    ///
+    /// Components outside of `0.0~1.0` are clamped to the limited range.
+    pub fn to_limited_range_u8(&self) -> (u8, u8, u8) {
+        let scale = |value: f32, max: f32| (16.0 + value * (max - 16.0)).round().clamp(16.0, max) as u8;
+        (scale(self.0, 235.0), scale(self.1, 240.0), scale(self.2, 240.0))
+    }
+
+    /// Reads a pixel from 8-bit limited ("studio") range. See `to_limited_range_u8`.
+    ///
+    /// Values outside of the limited range (such as "blacker than black") end up outside of `0.0~1.0`, rather than being clamped.
+    pub fn from_limited_range_u8(value: (u8, u8, u8)) -> YcbcrPixel {
+        let scale = |value: u8, max: f32| (value as f32 - 16.0) / (max - 16.0);
+        YcbcrPixel(scale(value.0, 235.0), scale(value.1, 240.0), scale(value.2, 240.0))
+    }
+
+    /// Converts an `RgbPixel` to YCbCr, using the given matrix.
+    pub fn from_rgb(rgb: &RgbPixel, matrix: YccMatrix) -> YcbcrPixel {
+        rgb_to_ycbcr(rgb.get(), matrix).into()
+    }
+
+    /// Converts the pixel to an `RgbPixel`, using the matrix it was made with.
+    #[must_use]
+    pub fn as_rgb(&self, matrix: YccMatrix) -> RgbPixel {
+        ycbcr_to_rgb(self.get(), matrix).into()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{conversions::YccMatrix, pixel::rgb::{colours, RgbPixel}};
+
+    use super::YcbcrPixel;
+
+    #[test]
+    fn round_trips_with_each_matrix() {
+        for matrix in [YccMatrix::Bt601, YccMatrix::Bt709] {
+            for rgb in [colours::RED, colours::AQUAMARINE, RgbPixel(0.2, 0.5, 0.8)] {
+                let (r, g, b) = YcbcrPixel::from_rgb(&rgb, matrix).as_rgb(matrix).get();
+                assert!((r - rgb.0).abs() < 1e-5 && (g - rgb.1).abs() < 1e-5 && (b - rgb.2).abs() < 1e-5, "{:?} with {:?}", rgb, matrix);
+            }
+        }
+    }
+
+    #[test]
+    fn greys_have_centred_chroma() {
+        for matrix in [YccMatrix::Bt601, YccMatrix::Bt709] {
+            let (y, cb, cr) = YcbcrPixel::from_rgb(&RgbPixel(0.4, 0.4, 0.4), matrix).get();
+
+            assert!((y - 0.4).abs() < 1e-6);
+            assert!((cb - 0.5).abs() < 1e-6 && (cr - 0.5).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn matrices_differ() {
+        let bt601 = YcbcrPixel::from_rgb(&colours::RED, YccMatrix::Bt601);
+        let bt709 = YcbcrPixel::from_rgb(&colours::RED, YccMatrix::Bt709);
+
+        assert!((bt601.0 - 0.299).abs() < 1e-6);
+        assert!((bt709.0 - 0.2126).abs() < 1e-6);
+    }
+
+    #[test]
+    fn limited_range_scaling() {
+        let white = YcbcrPixel::from_rgb(&colours::WHITE, YccMatrix::Bt709);
+        assert_eq!(white.to_limited_range_u8(), (235, 128, 128));
+
+        let black = YcbcrPixel::from_rgb(&colours::BLACK, YccMatrix::Bt709);
+        assert_eq!(black.to_limited_range_u8(), (16, 128, 128));
+
+        let pixel = YcbcrPixel::from_rgb(&RgbPixel(0.2, 0.5, 0.8), YccMatrix::Bt709);
+        let round_trip = YcbcrPixel::from_limited_range_u8(pixel.to_limited_range_u8());
+        assert!((round_trip.0 - pixel.0).abs() < 0.5 / 219.0);
+        assert!((round_trip.1 - pixel.1).abs() < 0.5 / 224.0 && (round_trip.2 - pixel.2).abs() < 0.5 / 224.0);
+
+        assert_eq!(YcbcrPixel::from_limited_range_u8((235, 240, 16)).get(), (1.0, 1.0, 0.0));
+        assert_eq!(YcbcrPixel(1.5, -0.5, 0.5).to_limited_range_u8(), (235, 16, 128));
+    }
+}
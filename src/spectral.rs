@@ -0,0 +1,225 @@
+use crate::pixel::{rgb::RgbPixel, xyz::XyzPixel};
+
+/// The wavelength (in nm) of the first entry in the colour matching function tables.
+const CMF_START: f32 = 380.0;
+
+/// The spacing (in nm) between entries in the colour matching function tables.
+const CMF_STEP: f32 = 10.0;
+
+/// The CIE 1931 2° standard observer colour matching functions, from 380nm to 780nm.
+const CIE_1931_2_DEGREE: [(f32, f32, f32); 41] = [
+    (0.001368, 0.000039, 0.00645), // 380
+    (0.004243, 0.00012, 0.02005), // 390
+    (0.01431, 0.000396, 0.06785), // 400
+    (0.04351, 0.00121, 0.2074), // 410
+    (0.13438, 0.004, 0.6456), // 420
+    (0.2839, 0.0116, 1.3856), // 430
+    (0.34828, 0.023, 1.74706), // 440
+    (0.3362, 0.038, 1.77211), // 450
+    (0.2908, 0.06, 1.6692), // 460
+    (0.19536, 0.09098, 1.28764), // 470
+    (0.09564, 0.13902, 0.81295), // 480
+    (0.03201, 0.20802, 0.46518), // 490
+    (0.0049, 0.323, 0.272), // 500
+    (0.0093, 0.503, 0.1582), // 510
+    (0.06327, 0.71, 0.07825), // 520
+    (0.1655, 0.862, 0.04216), // 530
+    (0.2904, 0.954, 0.0203), // 540
+    (0.43345, 0.99495, 0.00875), // 550
+    (0.5945, 0.995, 0.0039), // 560
+    (0.7621, 0.952, 0.0021), // 570
+    (0.9163, 0.87, 0.00165), // 580
+    (1.0263, 0.757, 0.0011), // 590
+    (1.0622, 0.631, 0.0008), // 600
+    (1.0026, 0.503, 0.00034), // 610
+    (0.85445, 0.381, 0.00019), // 620
+    (0.6424, 0.265, 0.00005), // 630
+    (0.4479, 0.175, 0.00002), // 640
+    (0.2835, 0.107, 0.0), // 650
+    (0.1649, 0.061, 0.0), // 660
+    (0.0874, 0.032, 0.0), // 670
+    (0.04677, 0.017, 0.0), // 680
+    (0.0227, 0.00821, 0.0), // 690
+    (0.011359, 0.004102, 0.0), // 700
+    (0.00579, 0.002091, 0.0), // 710
+    (0.002899, 0.001047, 0.0), // 720
+    (0.00144, 0.00052, 0.0), // 730
+    (0.00069, 0.000249, 0.0), // 740
+    (0.000332, 0.00012, 0.0), // 750
+    (0.000166, 0.00006, 0.0), // 760
+    (0.000083, 0.00003, 0.0), // 770
+    (0.000042, 0.000015, 0.0), // 780
+];
+
+/// The CIE 1964 10° standard observer colour matching functions, from 380nm to 780nm.
+const CIE_1964_10_DEGREE: [(f32, f32, f32); 41] = [
+    (0.00016, 0.000017, 0.000705), // 380
+    (0.002362, 0.000253, 0.010482), // 390
+    (0.01911, 0.002004, 0.086011), // 400
+    (0.084736, 0.008756, 0.389366), // 410
+    (0.204492, 0.021391, 0.972542), // 420
+    (0.314679, 0.038676, 1.55348), // 430
+    (0.383734, 0.062077, 1.96728), // 440
+    (0.370702, 0.089456, 1.9948), // 450
+    (0.302273, 0.128201, 1.74537), // 460
+    (0.195618, 0.18519, 1.31756), // 470
+    (0.080507, 0.253589, 0.772125), // 480
+    (0.016172, 0.339133, 0.415254), // 490
+    (0.003816, 0.460777, 0.218502), // 500
+    (0.037465, 0.606741, 0.112044), // 510
+    (0.117749, 0.761757, 0.060709), // 520
+    (0.236491, 0.875211, 0.030451), // 530
+    (0.376772, 0.961988, 0.013676), // 540
+    (0.529826, 0.991761, 0.003988), // 550
+    (0.705224, 0.99734, 0.0), // 560
+    (0.878655, 0.955552, 0.0), // 570
+    (1.01416, 0.868934, 0.0), // 580
+    (1.11852, 0.777405, 0.0), // 590
+    (1.124, 0.658341, 0.0), // 600
+    (1.03048, 0.527963, 0.0), // 610
+    (0.856297, 0.398057, 0.0), // 620
+    (0.647467, 0.283493, 0.0), // 630
+    (0.431567, 0.179828, 0.0), // 640
+    (0.268329, 0.107633, 0.0), // 650
+    (0.152568, 0.060281, 0.0), // 660
+    (0.081261, 0.0318, 0.0), // 670
+    (0.040851, 0.015905, 0.0), // 680
+    (0.019941, 0.007749, 0.0), // 690
+    (0.009577, 0.003718, 0.0), // 700
+    (0.004553, 0.001768, 0.0), // 710
+    (0.002175, 0.000846, 0.0), // 720
+    (0.001045, 0.000407, 0.0), // 730
+    (0.000508, 0.000199, 0.0), // 740
+    (0.000251, 0.000098, 0.0), // 750
+    (0.000126, 0.00005, 0.0), // 760
+    (0.000065, 0.000025, 0.0), // 770
+    (0.000033, 0.000013, 0.0), // 780
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// The standard observer to use when turning a spectrum into a colour.
+pub enum Observer {
+    /// The CIE 1931 2° observer. This is what most colour spaces (including sRGB) are based on.
+    Cie1931,
+    /// The CIE 1964 10° observer. Better suited to colours covering a large part of the field of view.
+    Cie1964,
+}
+
+impl Observer {
+    fn table(&self) -> &'static [(f32, f32, f32); 41] {
+        match self {
+            Observer::Cie1931 => &CIE_1931_2_DEGREE,
+            Observer::Cie1964 => &CIE_1964_10_DEGREE,
+        }
+    }
+
+    /// Retrieves the (x̄, ȳ, z̄) colour matching functions at a wavelength (in nm), linearly interpolating
+    /// between table entries. Wavelengths outside of 380nm to 780nm aren't visible, and return zero.
+    pub fn colour_matching_functions(&self, wavelength: f32) -> (f32, f32, f32) {
+        let table = self.table();
+        let position = (wavelength - CMF_START) / CMF_STEP;
+
+        if position < 0.0 || position > (table.len() - 1) as f32 {
+            return (0.0, 0.0, 0.0);
+        }
+
+        let index = (position as usize).min(table.len() - 2);
+        let t = position - index as f32;
+        let (a, b) = (table[index], table[index + 1]);
+
+        (
+            a.0 + (b.0 - a.0) * t,
+            a.1 + (b.1 - a.1) * t,
+            a.2 + (b.2 - a.2) * t,
+        )
+    }
+
+    /// The integral of ȳ across the visible spectrum - used to normalize the luminance.
+    fn y_integral(&self) -> f32 {
+        let table = self.table();
+        table
+            .windows(2)
+            .map(|pair| (pair[0].1 + pair[1].1) / 2.0 * CMF_STEP)
+            .sum()
+    }
+}
+
+/// Integrates a spectral power distribution against the colour matching functions of an observer.
+///
+/// `samples` is a list of `(wavelength, power)` pairs, with wavelengths in nm. They don't need to be
+/// evenly spaced (or sorted) - the integration is done using the trapezoidal rule between each pair of
+/// neighbouring samples.
+///
+/// The result is normalized so that a flat (equal-energy) spectrum with a power of `1.0` has a
+/// luminance (Y) of `1.0`.
+pub fn spd_to_xyz(samples: &[(f32, f32)], observer: Observer) -> XyzPixel {
+    let mut samples = samples.to_vec();
+    samples.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+    let weighted = |(wavelength, power): (f32, f32)| {
+        let (x, y, z) = observer.colour_matching_functions(wavelength);
+        (x * power, y * power, z * power)
+    };
+
+    let (x, y, z) = samples
+        .windows(2)
+        .map(|pair| {
+            let width = pair[1].0 - pair[0].0;
+            let (a, b) = (weighted(pair[0]), weighted(pair[1]));
+            (
+                (a.0 + b.0) / 2.0 * width,
+                (a.1 + b.1) / 2.0 * width,
+                (a.2 + b.2) / 2.0 * width,
+            )
+        })
+        .fold((0.0, 0.0, 0.0), |acc, area| (acc.0 + area.0, acc.1 + area.1, acc.2 + area.2));
+
+    let normalization = observer.y_integral();
+    XyzPixel(x / normalization, y / normalization, z / normalization)
+}
+
+/// Converts a spectral power distribution to RGB - see `spd_to_xyz`.
+///
+/// **Note:** No chromatic adaptation is done, so the XYZ is treated as if it were relative to D65.
+pub fn spd_to_rgb(samples: &[(f32, f32)], observer: Observer) -> RgbPixel {
+    spd_to_xyz(samples, observer).as_rgb()
+}
+
+#[cfg(test)]
+mod test {
+    use super::{spd_to_xyz, Observer};
+
+    #[test]
+    fn equal_energy_is_white_point_e() {
+        let flat: Vec<(f32, f32)> = (380..=780).step_by(5).map(|wavelength| (wavelength as f32, 1.0)).collect();
+
+        for observer in [Observer::Cie1931, Observer::Cie1964] {
+            let xyz = spd_to_xyz(&flat, observer);
+            let (x, y) = xyz.chromaticity();
+
+            assert!((x - 1.0 / 3.0).abs() < 0.005, "x was {}", x);
+            assert!((y - 1.0 / 3.0).abs() < 0.005, "y was {}", y);
+            assert!((xyz.1 - 1.0).abs() < 0.01);
+        }
+    }
+
+    #[test]
+    fn uneven_spacing_is_integrated() {
+        let mut uneven = vec![(380.0, 1.0), (400.0, 1.0), (405.0, 1.0)];
+        uneven.extend((410..=780).step_by(10).map(|wavelength| (wavelength as f32, 1.0)));
+
+        let (x, y) = spd_to_xyz(&uneven, Observer::Cie1931).chromaticity();
+
+        assert!((x - 1.0 / 3.0).abs() < 0.005);
+        assert!((y - 1.0 / 3.0).abs() < 0.005);
+    }
+
+    #[test]
+    fn monochromatic_light_is_on_the_spectral_locus() {
+        let narrow = [(549.0, 0.0), (550.0, 1.0), (551.0, 0.0)];
+        let (x, y) = spd_to_xyz(&narrow, Observer::Cie1931).chromaticity();
+
+        assert!((x - 0.3016).abs() < 0.001);
+        assert!((y - 0.6923).abs() < 0.001);
+    }
+}